@@ -0,0 +1,66 @@
+//! Build script collecting the solution modules
+//!
+//! Every file `src/solutions/dayNN.rs` is declared as module `dayNN`, and its
+//! `DayNN` type implementing the `Solution` trait is added to the registry,
+//! titled by the first line of the module docs. Thus, adding a day only
+//! requires adding its file.
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// Extracts the puzzle title from the first line of a module, which reads
+/// `//! # Solutions for Day NN - <title>`
+fn read_title(path: &Path, day: &str) -> String {
+    let content = fs::read_to_string(path).expect("Failed reading solution module!");
+    let prefix = format!("//! # Solutions for Day {} - ", day);
+    match content
+        .lines()
+        .next()
+        .and_then(|line| line.strip_prefix(&prefix))
+    {
+        Some(title) if !title.trim().is_empty() => title.trim().to_string(),
+        _ => panic!("{:?} should start with `{}<title>`!", path, prefix),
+    }
+}
+
+fn main() {
+    let solutions_dir = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("src/solutions");
+    println!("cargo:rerun-if-changed={}", solutions_dir.display());
+
+    let mut modules = fs::read_dir(&solutions_dir)
+        .expect("Failed reading solutions directory!")
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+        .filter_map(|name| Some(name.strip_suffix(".rs")?.to_string()))
+        .filter(|name| {
+            name.len() == 5
+                && name.starts_with("day")
+                && name[3..].chars().all(|c| c.is_ascii_digit())
+        })
+        .collect::<Vec<_>>();
+    modules.sort();
+
+    let mut code = String::new();
+    for module in modules.iter() {
+        code += &format!(
+            "#[path = {:?}]\npub mod {};\n",
+            solutions_dir.join(format!("{}.rs", module)),
+            module
+        );
+    }
+    code += "\n/// Constructs the registrations of all solution modules, ordered by day\n";
+    code += "fn registrations() -> Vec<Registration> {\n    vec![\n";
+    for module in modules.iter() {
+        let title = read_title(&solutions_dir.join(format!("{}.rs", module)), &module[3..]);
+        code += &format!(
+            "        Registration::of::<{}::Day{}>({:?}),\n",
+            module,
+            &module[3..],
+            title
+        );
+    }
+    code += "    ]\n}\n";
+
+    let out_path = Path::new(&env::var("OUT_DIR").unwrap()).join("solutions.rs");
+    fs::write(out_path, code).expect("Failed writing solution registry!");
+}
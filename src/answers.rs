@@ -0,0 +1,192 @@
+//! Answers of solutions and their verification against recorded answers
+//!
+//! The answers are stored in one TOML file per input mode, with a table per
+//! day and an entry per part, e.g.:
+//!
+//! ```toml
+//! [day05]
+//! part1 = 6572
+//! part2 = 21466
+//! ```
+//!
+//! Answers exceeding the TOML integer range or non-numeric ones are given as
+//! strings, e.g. `part2 = "340282366920938463463374607431768211455"`.
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use serde::{Serialize, Serializer};
+
+use crate::error::AocError;
+use crate::info;
+use crate::utils::{Context, InputMode};
+
+/// The answer computed by a solution
+///
+/// Answers are compared and printed via their textual representation, such
+/// that e.g. `Int(5)` and `BigInt(5)` are regarded as the same answer.
+#[derive(Clone, Debug)]
+pub enum Answer {
+    Int(i64),
+    BigInt(u128),
+    Text(String),
+}
+
+impl Answer {
+    /// Constructs an integer answer from an unsigned integer, which is only a
+    /// big integer if it exceeds the range of `i64`
+    pub fn from_unsigned(value: u128) -> Answer {
+        match i64::try_from(value) {
+            Ok(value) => Answer::Int(value),
+            Err(_) => Answer::BigInt(value),
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Int(value) => fmt::Display::fmt(value, f),
+            Answer::BigInt(value) => fmt::Display::fmt(value, f),
+            Answer::Text(text) => f.pad(text),
+        }
+    }
+}
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Answer) -> bool {
+        self.to_string() == other.to_string()
+    }
+}
+
+impl Eq for Answer {}
+
+/// Serializes integers as JSON numbers; big integers are serialized as
+/// strings, since most JSON consumers cannot represent them exactly
+impl Serialize for Answer {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Answer::Int(value) => s.serialize_i64(*value),
+            Answer::BigInt(value) => s.serialize_str(&value.to_string()),
+            Answer::Text(text) => s.serialize_str(text),
+        }
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(value: i64) -> Answer {
+        Answer::Int(value)
+    }
+}
+
+impl From<u128> for Answer {
+    fn from(value: u128) -> Answer {
+        Answer::BigInt(value)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(text: String) -> Answer {
+        Answer::Text(text)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Answer {
+        Answer::Text(text.to_string())
+    }
+}
+
+// -----------------------------------------------------------------------------
+
+/// The outcome of comparing a solution against the recorded answer
+pub enum CheckResult {
+    Pass,
+    Fail { expected: Answer },
+    Unknown,
+}
+
+impl CheckResult {
+    /// Compares a solution against the expected answer, if it is known
+    pub fn compare(expected: Option<Answer>, solution: &Answer) -> CheckResult {
+        match expected {
+            Some(expected) if expected == *solution => CheckResult::Pass,
+            Some(expected) => CheckResult::Fail { expected },
+            None => CheckResult::Unknown,
+        }
+    }
+
+    pub fn is_fail(&self) -> bool {
+        matches!(self, CheckResult::Fail { .. })
+    }
+}
+
+impl fmt::Display for CheckResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CheckResult::Pass => write!(f, "PASS"),
+            CheckResult::Fail { expected } => write!(f, "FAIL (expected {})", expected),
+            CheckResult::Unknown => write!(f, "UNKNOWN"),
+        }
+    }
+}
+
+/// The recorded answers for a certain input mode
+pub struct Answers {
+    table: toml::Table,
+}
+
+impl Answers {
+    /// Loads the answers file for the given input mode
+    ///
+    /// If there is no answers file, all answers are regarded as unknown.
+    pub fn load(ctx: &Context, input_dir: &str) -> Result<Answers, AocError> {
+        let dir = Path::new(input_dir);
+        let filepath = match ctx.input_mode {
+            InputMode::Test => dir.join("answers_test.toml"),
+            InputMode::Full => dir.join("answers.toml"),
+        };
+
+        if !filepath.exists() {
+            info!(ctx, "No answers file found at:\n  {:?}", &filepath);
+            return Ok(Answers {
+                table: toml::Table::new(),
+            });
+        }
+
+        info!(ctx, "Loading answers from:\n  {:?}", &filepath);
+        let content = fs::read_to_string(&filepath).map_err(|source| AocError::Io {
+            path: filepath.clone(),
+            source,
+        })?;
+        let table = content.parse::<toml::Table>().map_err(|err| {
+            AocError::InvalidInput(format!("Invalid answers file {:?}:\n{}", &filepath, err))
+        })?;
+
+        return Ok(Answers { table });
+    }
+
+    /// Retrieves the recorded answer for a day and part, if available
+    pub fn get(&self, day: u8, part: u8) -> Option<Answer> {
+        let day_table = self.table.get(&format!("day{:02}", day))?.as_table()?;
+        return get_part_answer(day_table, part);
+    }
+
+    /// Compares a solution against the recorded answer
+    pub fn check(&self, day: u8, part: u8, solution: &Answer) -> CheckResult {
+        CheckResult::compare(self.get(day, part), solution)
+    }
+}
+
+/// Retrieves the answer for a part from a table with `partN` entries
+///
+/// Strings are taken as text answers; since answers compare via their textual
+/// representation, they may also hold big integers.
+pub fn get_part_answer(table: &toml::Table, part: u8) -> Option<Answer> {
+    match table.get(&format!("part{}", part))? {
+        toml::Value::Integer(value) => Some(Answer::Int(*value)),
+        toml::Value::String(text) => Some(Answer::Text(text.clone())),
+        _ => None,
+    }
+}
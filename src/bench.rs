@@ -0,0 +1,91 @@
+//! Benchmarking of solution functions via repeated runs
+//!
+//! The parsing and solving phases are timed separately, such that it is
+//! visible which of the two dominates the run time of a solution.
+
+use std::time::{Duration, Instant};
+
+use crate::{Answer, AocError, Context, SolutionFunc};
+
+/// Summary statistics of a number of timing samples
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    /// Computes the statistics; expects at least one sample
+    pub fn from_samples(samples: &[Duration]) -> Stats {
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+
+        let n = sorted.len();
+        let median = match n.is_multiple_of(2) {
+            true => (sorted[n / 2 - 1] + sorted[n / 2]) / 2,
+            false => sorted[n / 2],
+        };
+
+        let secs = sorted.iter().map(|d| d.as_secs_f64()).collect::<Vec<_>>();
+        let mean = secs.iter().sum::<f64>() / n as f64;
+        let variance = secs.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / n as f64;
+
+        return Stats {
+            min: sorted[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        };
+    }
+}
+
+/// The outcome of benchmarking a solution function
+pub struct BenchResult {
+    pub answer: Answer,
+    pub parse: Stats,
+    pub solve: Stats,
+}
+
+/// Benchmarks a solution function by invoking it `num_runs` times
+///
+/// Before the timed runs, `num_warmup` untimed runs are carried out. Each run
+/// parses the input anew, such that both phases are measured in every run.
+/// To not measure output, the context should typically be a quiet one.
+pub fn bench(
+    func: &SolutionFunc,
+    input: &String,
+    ctx: &Context,
+    num_warmup: usize,
+    num_runs: usize,
+) -> Result<BenchResult, AocError> {
+    if num_runs == 0 {
+        return Err(AocError::Usage(String::from(
+            "Need at least one benchmark run!",
+        )));
+    }
+
+    for _ in 0..num_warmup {
+        func.run(input, ctx)?;
+    }
+
+    let mut parse_times = Vec::with_capacity(num_runs);
+    let mut solve_times = Vec::with_capacity(num_runs);
+    let mut answer = Answer::Int(0);
+
+    for _ in 0..num_runs {
+        let start = Instant::now();
+        let parsed = (func.parse)(input)?;
+        parse_times.push(start.elapsed());
+
+        let start = Instant::now();
+        answer = (func.solve)(parsed.as_ref(), ctx)?;
+        solve_times.push(start.elapsed());
+    }
+
+    return Ok(BenchResult {
+        answer,
+        parse: Stats::from_samples(&parse_times),
+        solve: Stats::from_samples(&solve_times),
+    });
+}
@@ -0,0 +1,315 @@
+//! Definition of the command line interface
+
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::{Args, Parser, Subcommand};
+
+use aoc21::driver::OutputFormat;
+use aoc21::{InputMode, InputSource, Verbosity};
+
+/// Solutions to the Advent of Code 2021
+#[derive(Parser)]
+#[command(name = "aoc21", version, propagate_version = true)]
+pub struct Cli {
+    #[command(flatten)]
+    pub global: GlobalArgs,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+/// The options available for all subcommands
+#[derive(Args)]
+pub struct GlobalArgs {
+    /// Only print the results
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+
+    /// Print intermediate results; given twice, print debug traces of every step
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+
+    /// Never fetch missing inputs from the network
+    #[arg(long, global = true)]
+    pub offline: bool,
+}
+
+impl GlobalArgs {
+    pub fn verbosity(&self) -> Verbosity {
+        match (self.quiet, self.verbose) {
+            (true, _) => Verbosity::Quiet,
+            (false, 0) => Verbosity::Normal,
+            (false, 1) => Verbosity::Verbose,
+            (false, _) => Verbosity::Debug,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Run the solutions for a day and part, or for a selection of days
+    Run(RunArgs),
+
+    /// Run the solutions and compare their answers against the recorded ones
+    Check(CheckArgs),
+
+    /// Benchmark the solutions with repeated runs
+    Bench(BenchArgs),
+
+    /// Show the implementation status of all days, along with their inputs and
+    /// recorded answers
+    List,
+
+    /// Generate the module and input files for a new day
+    New {
+        /// The day to scaffold
+        #[arg(value_parser = parse_day)]
+        day: u8,
+
+        /// The puzzle title
+        title: Vec<String>,
+    },
+
+    /// Submit the answer for a day and part
+    Submit(SubmitArgs),
+
+    /// Start an interactive shell for exploring solutions
+    Repl,
+}
+
+/// The selection of the input mode, i.e. the full or the test input
+#[derive(Args)]
+pub struct InputModeArgs {
+    /// Use the full puzzle input (the default)
+    #[arg(long, conflicts_with = "test")]
+    pub full: bool,
+
+    /// Use the test input from the puzzle text
+    #[arg(long)]
+    pub test: bool,
+}
+
+impl InputModeArgs {
+    pub fn input_mode(&self) -> InputMode {
+        match self.test {
+            true => InputMode::Test,
+            false => InputMode::Full,
+        }
+    }
+}
+
+/// The options for distributing solutions over worker threads
+#[derive(Args)]
+pub struct PoolArgs {
+    /// The number of solutions to run in parallel
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u16).range(1..))]
+    pub jobs: u16,
+
+    /// Give up on a solution after this many seconds, reporting a TIMEOUT
+    #[arg(long, value_name = "SECS", value_parser = parse_timeout)]
+    pub timeout: Option<Duration>,
+}
+
+#[derive(Args)]
+pub struct RunArgs {
+    /// The day, a range of days like `1..=8`, or `all`
+    #[arg(value_parser = parse_days)]
+    pub days: RangeInclusive<u8>,
+
+    /// The part; if not given, both parts are run
+    #[arg(value_parser = clap::value_parser!(u8).range(1..=2))]
+    pub part: Option<u8>,
+
+    #[command(flatten)]
+    pub input_mode: InputModeArgs,
+
+    /// Read the input from a file, or from stdin if `-`
+    #[arg(long, value_name = "PATH", requires = "part", value_parser = parse_input_source)]
+    pub input: Option<InputSource>,
+
+    /// Compare the answers against the recorded ones
+    #[arg(long)]
+    pub check: bool,
+
+    /// The format to print the results in
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+
+    #[command(flatten)]
+    pub pool: PoolArgs,
+
+    /// The size of the sliding window, overriding the default of the part
+    /// (day 01 only)
+    #[arg(long, value_name = "K", value_parser = clap::value_parser!(u16).range(1..))]
+    pub window: Option<u16>,
+
+    /// Export the trajectory of the submarine to a `.csv` or `.svg` file
+    /// (day 02 only)
+    #[arg(long, value_name = "PATH", requires = "part")]
+    pub export: Option<PathBuf>,
+
+    /// Rerun the solution whenever its input changes
+    #[arg(long, requires = "part", conflicts_with = "format")]
+    pub watch: bool,
+
+    /// Like --watch, and rebuild and restart when the day's source changes
+    #[arg(long, requires = "part", conflicts_with = "format")]
+    pub watch_src: bool,
+}
+
+#[derive(Args)]
+pub struct CheckArgs {
+    /// The day, a range of days like `1..=8`, or `all`
+    #[arg(value_parser = parse_days)]
+    pub days: RangeInclusive<u8>,
+
+    #[command(flatten)]
+    pub input_mode: InputModeArgs,
+
+    /// Check against all named examples of the days instead
+    #[arg(long, alias = "test-all", conflicts_with_all = ["full", "test"])]
+    pub examples: bool,
+
+    /// The format to print the results in
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+
+    #[command(flatten)]
+    pub pool: PoolArgs,
+}
+
+#[derive(Args)]
+pub struct BenchArgs {
+    /// The day, a range of days like `1..=8`, or `all`
+    #[arg(value_parser = parse_days)]
+    pub days: RangeInclusive<u8>,
+
+    /// The part; if not given, both parts are benchmarked
+    #[arg(value_parser = clap::value_parser!(u8).range(1..=2))]
+    pub part: Option<u8>,
+
+    #[command(flatten)]
+    pub input_mode: InputModeArgs,
+
+    /// Read the input from a file, or from stdin if `-`
+    #[arg(long, value_name = "PATH", requires = "part", value_parser = parse_input_source)]
+    pub input: Option<InputSource>,
+
+    /// The number of timed runs per solution
+    #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+    pub runs: u32,
+
+    /// Also benchmark the alternative implementations of the solutions
+    #[arg(long)]
+    pub variants: bool,
+}
+
+#[derive(Args)]
+pub struct SubmitArgs {
+    /// The day of the puzzle
+    #[arg(value_parser = parse_day)]
+    pub day: u8,
+
+    /// The part of the puzzle
+    #[arg(value_parser = clap::value_parser!(u8).range(1..=2))]
+    pub part: u8,
+
+    /// The answer; if not given, it is computed from the full input
+    pub answer: Option<String>,
+
+    /// Read the input from a file, or from stdin if `-`
+    #[arg(long, value_name = "PATH", value_parser = parse_input_source)]
+    pub input: Option<InputSource>,
+
+    /// Submit even if the answer contradicts a recorded too-high/too-low bound
+    #[arg(long)]
+    pub force: bool,
+}
+
+// -----------------------------------------------------------------------------
+
+/// Parses a day, which must be between 1 and 25
+fn parse_day(arg: &str) -> Result<u8, String> {
+    match arg.parse::<u8>() {
+        Ok(day) if (1..=25).contains(&day) => Ok(day),
+        _ => Err(format!("invalid day {:?}, should be between 1 and 25", arg)),
+    }
+}
+
+/// Parses a selection of days, i.e. a single day, `all`, or a range like
+/// `1..=8` or `1..9`
+fn parse_days(arg: &str) -> Result<RangeInclusive<u8>, String> {
+    if arg == "all" {
+        return Ok(1..=25);
+    }
+    let days = if let Some((start, end)) = arg.split_once("..=") {
+        parse_day(start)?..=parse_day(end)?
+    } else if let Some((start, end)) = arg.split_once("..") {
+        // The exclusive end may be one past the last day, e.g. in `1..26`
+        let last = end.parse::<u8>().ok().and_then(|end| end.checked_sub(1));
+        match last {
+            Some(last) if last <= 25 => parse_day(start)?..=last,
+            _ => {
+                return Err(format!(
+                    "invalid end of range {:?}, should be between 1 and 26",
+                    end
+                ))
+            }
+        }
+    } else {
+        let day = parse_day(arg)?;
+        day..=day
+    };
+    match days.is_empty() {
+        true => Err(format!("empty range of days {:?}", arg)),
+        false => Ok(days),
+    }
+}
+
+/// Parses an input file path, or `-` for stdin
+fn parse_input_source(arg: &str) -> Result<InputSource, String> {
+    Ok(InputSource::from_arg(arg))
+}
+
+/// Parses a timeout given in seconds
+fn parse_timeout(arg: &str) -> Result<Duration, String> {
+    match arg.parse::<f64>() {
+        Ok(secs) if secs > 0. && secs.is_finite() => Ok(Duration::from_secs_f64(secs)),
+        _ => Err(format!(
+            "invalid timeout {:?}, should be a positive number of seconds",
+            arg
+        )),
+    }
+}
+
+// -----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_days() {
+        assert_eq!(parse_days("all"), Ok(1..=25));
+        assert_eq!(parse_days("5"), Ok(5..=5));
+        assert_eq!(parse_days("1..=8"), Ok(1..=8));
+        assert_eq!(parse_days("1..9"), Ok(1..=8));
+        assert_eq!(parse_days("1..26"), Ok(1..=25));
+    }
+
+    #[test]
+    fn rejects_invalid_days() {
+        for arg in ["0", "26", "1..27", "1..=26", "0..5", "2..x", "1..0", ""] {
+            assert!(parse_days(arg).is_err(), "{:?} should be rejected", arg);
+        }
+    }
+
+    #[test]
+    fn rejects_empty_ranges() {
+        for arg in ["1..1", "5..3", "5..=4"] {
+            assert!(parse_days(arg).is_err(), "{:?} should be rejected", arg);
+        }
+    }
+}
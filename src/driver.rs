@@ -0,0 +1,348 @@
+//! Drivers running, checking and benchmarking a selection of solutions
+//!
+//! These print the per-solution results and summaries of the CLI's `run`,
+//! `check` and `bench` subcommands, in the given [`OutputFormat`].
+
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::answers::{Answers, CheckResult};
+use crate::bench::{bench, Stats};
+use crate::examples::load_examples;
+use crate::parallel::run_parallel;
+use crate::report::RunRecord;
+use crate::{
+    get_solution_functions, get_solutions, info, AocError, Context, InputMode, InputSource,
+    Verbosity,
+};
+
+/// The number of untimed runs before the timed benchmark runs
+const BENCH_WARMUP_RUNS: usize = 1;
+
+/// The formats the results can be printed in
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+/// Returns the sorted (day, part) keys of all implemented solutions in a range
+///
+/// Parts which are still stubs, see [`crate::Solution::IMPLEMENTED`], are
+/// skipped, such that scaffolding a day does not fail the summaries.
+pub fn select_solutions(days: &RangeInclusive<u8>) -> Vec<(u8, u8)> {
+    let mut keys = Vec::new();
+    for registration in get_solutions() {
+        if !days.contains(&registration.day) {
+            continue;
+        }
+        for (part, implemented) in (1..).zip(registration.implemented) {
+            if implemented {
+                keys.push((registration.day, part));
+            }
+        }
+    }
+    keys.sort();
+    return keys;
+}
+
+/// Formats a duration in milliseconds
+fn format_ms(duration: &Duration) -> String {
+    format!("{:9.3} ms", duration.as_secs_f64() * 1000.)
+}
+
+/// Benchmarks the selected solutions and prints the timing statistics
+///
+/// Inputs are loaded and solutions are invoked without any output, regardless
+/// of the verbosity; the parsing and solving phases of each solution are
+/// reported separately. With `with_variants`, the alternative implementations
+/// of each solution are benchmarked on the same input as well, and answers
+/// differing from the registered solution's are reported as errors. Returns
+/// whether all succeeded.
+pub fn run_bench(
+    keys: &[(u8, u8)],
+    ctx: &Context,
+    source: &InputSource,
+    num_runs: usize,
+    with_variants: bool,
+) -> Result<bool, AocError> {
+    let funcs = get_solution_functions();
+    let solutions = get_solutions();
+    let quiet_ctx = Context::new(ctx.input_mode, Verbosity::Quiet);
+    let mut all_ok = true;
+
+    info!(
+        ctx,
+        "\nBenchmarking {} solution(s) with {} run(s) each ...\n",
+        keys.len(),
+        num_runs
+    );
+    let label = |day: u8, part: u8, variant: &str| match with_variants {
+        true => format!("{:>3}  {:>4}  {:>7}", day, part, variant),
+        false => format!("{:>3}  {:>4}", day, part),
+    };
+    println!(
+        "{}  {:>5}  {:>12}  {:>12}  {:>12}  {:>12}",
+        match with_variants {
+            true => format!("{:>3}  {:>4}  {:>7}", "Day", "Part", "Variant"),
+            false => format!("{:>3}  {:>4}", "Day", "Part"),
+        },
+        "Phase",
+        "Min",
+        "Median",
+        "Mean",
+        "Stddev"
+    );
+    for &(day, part) in keys.iter() {
+        let mut candidates = vec![("-", funcs[&(day, part)])];
+        if with_variants {
+            let registration = solutions.iter().find(|solution| solution.day == day);
+            for variant in registration.map_or_else(Vec::new, |reg| (reg.variants)()) {
+                candidates.push((variant.name, variant.parts[part as usize - 1]));
+            }
+        }
+
+        let input = match source.read(day, &ctx.input_mode) {
+            Ok(input) => input,
+            Err(err) => {
+                all_ok = false;
+                println!("{}  ERROR:  {}", label(day, part, "-"), err);
+                continue;
+            }
+        };
+        let mut expected = None;
+        for (name, func) in candidates {
+            let result = match bench(&func, &input, &quiet_ctx, BENCH_WARMUP_RUNS, num_runs) {
+                Ok(result) => result,
+                Err(err) => {
+                    all_ok = false;
+                    println!("{}  ERROR:  {}", label(day, part, name), err);
+                    continue;
+                }
+            };
+
+            let print_stats = |phase: &str, stats: &Stats| {
+                println!(
+                    "{}  {:>5}  {}  {}  {}  {}",
+                    label(day, part, name),
+                    phase,
+                    format_ms(&stats.min),
+                    format_ms(&stats.median),
+                    format_ms(&stats.mean),
+                    format_ms(&stats.stddev)
+                );
+            };
+            print_stats("parse", &result.parse);
+            print_stats("solve", &result.solve);
+
+            match &expected {
+                None => expected = Some(result.answer),
+                Some(answer) if answer.to_string() != result.answer.to_string() => {
+                    all_ok = false;
+                    println!(
+                        "{}  ERROR:  answer {} differs from {}",
+                        label(day, part, name),
+                        result.answer,
+                        answer
+                    );
+                }
+                Some(_) => (),
+            }
+        }
+    }
+
+    return Ok(all_ok);
+}
+
+/// Applies the checks to the records and returns whether none of them failed
+pub fn check_records(
+    records: &mut [RunRecord],
+    ctx: &Context,
+    input_dir: &str,
+) -> Result<bool, AocError> {
+    let answers = Answers::load(ctx, input_dir)?;
+    let mut all_passed = true;
+
+    for record in records.iter_mut() {
+        let check_result = match &record.answer {
+            Some(answer) => answers.check(record.day, record.part, answer),
+            None => CheckResult::Unknown,
+        };
+        all_passed &= !check_result.is_fail();
+        record.check = Some(check_result.to_string());
+    }
+
+    return Ok(all_passed);
+}
+
+/// Prints records as a JSON document, i.e. an object or an array of objects
+pub fn print_json<T: Serialize>(document: &T) {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(document).expect("Failed serializing to JSON!")
+    );
+}
+
+/// Runs all registered solutions for the selected days and prints a summary
+///
+/// If `check` is set, compares the answers against the recorded ones. Returns
+/// whether all solutions could be computed and none of the checks failed.
+#[allow(clippy::too_many_arguments)]
+pub fn run_all(
+    days: RangeInclusive<u8>,
+    ctx: &Context,
+    source: &InputSource,
+    input_dir: &str,
+    check: bool,
+    format: OutputFormat,
+    jobs: usize,
+    timeout: Option<Duration>,
+) -> Result<bool, AocError> {
+    let funcs = get_solution_functions();
+
+    // Run all solutions, keeping track of answers and run time; errors,
+    // timeouts and panics are reported in the summary instead of aborting
+    // the whole run
+    let keys = select_solutions(&days);
+    let mut records = run_parallel(&keys, &funcs, ctx, source, jobs, timeout);
+    for record in records.iter() {
+        if let Some(err) = &record.error {
+            eprintln!(
+                "Error in day {:02} part {}:  {}",
+                record.day, record.part, err
+            );
+        }
+    }
+
+    // Optionally check against recorded answers, then print the summary
+    let mut all_ok = records.iter().all(|record| record.error.is_none());
+    if check {
+        all_ok &= check_records(&mut records, ctx, input_dir)?;
+    }
+
+    if format == OutputFormat::Json {
+        print_json(&records);
+        return Ok(all_ok);
+    }
+
+    info!(ctx, "\n---- Summary ----\n");
+    println!(
+        "{:>3}  {:>4}  {:>20}  {:>12}  Check",
+        "Day", "Part", "Answer", "Time"
+    );
+    for record in records.iter() {
+        let answer = match (&record.answer, &record.error) {
+            (Some(answer), _) => answer,
+            (None, error) => {
+                let status = match error {
+                    Some(AocError::Timeout(_)) => "TIMEOUT",
+                    Some(AocError::Panic(_)) => "FAIL",
+                    _ => "ERROR",
+                };
+                println!("{:>3}  {:>4}  {:>20}", record.day, record.part, status);
+                continue;
+            }
+        };
+        println!(
+            "{:>3}  {:>4}  {:>20}  {:>9.3} ms  {}",
+            record.day,
+            record.part,
+            answer,
+            record.time_ms(),
+            record.check.as_deref().unwrap_or("-")
+        );
+    }
+
+    return Ok(all_ok);
+}
+
+/// Runs all registered solutions for the selected days on each of the days'
+/// examples and prints the per-example results
+///
+/// Returns whether all solutions could be computed and none of the examples'
+/// expected answers were missed.
+pub fn run_test_all(
+    days: RangeInclusive<u8>,
+    verbosity: Verbosity,
+    input_dir: &str,
+    format: OutputFormat,
+) -> Result<bool, AocError> {
+    let funcs = get_solution_functions();
+    let ctx = Context::new(InputMode::Test, verbosity);
+    let keys = select_solutions(&days);
+
+    let mut records = Vec::new();
+    let mut all_ok = true;
+    let mut day_keys = keys.iter().map(|(day, _)| *day).collect::<Vec<_>>();
+    day_keys.dedup();
+    for day in day_keys {
+        let examples = load_examples(day, &ctx, input_dir)?;
+        for example in examples.iter() {
+            for &(_, part) in keys.iter().filter(|(d, _)| *d == day) {
+                info!(
+                    ctx,
+                    "\n---- Day {:02} --- Part {} --- Example {:?} ----\n", day, part, example.name
+                );
+                let mut record = RunRecord::run_input(
+                    day,
+                    part,
+                    &funcs[&(day, part)],
+                    &ctx,
+                    example.path.clone(),
+                    &example.input,
+                );
+                record.example = Some(example.name.clone());
+                match &record.answer {
+                    Some(answer) => {
+                        let check_result = example.check(part, answer);
+                        all_ok &= !check_result.is_fail();
+                        record.check = Some(check_result.to_string());
+                    }
+                    None => all_ok = false,
+                }
+                if let Some(err) = &record.error {
+                    eprintln!("Error:  {}", err);
+                }
+                records.push(record);
+            }
+        }
+    }
+
+    if format == OutputFormat::Json {
+        print_json(&records);
+        return Ok(all_ok);
+    }
+
+    info!(ctx, "\n---- Summary ----\n");
+    println!(
+        "{:>3}  {:>4}  {:<20}  {:>20}  {:>12}  Check",
+        "Day", "Part", "Example", "Answer", "Time"
+    );
+    for record in records.iter() {
+        let example = record.example.as_deref().unwrap_or("-");
+        let answer = match &record.answer {
+            Some(answer) => answer,
+            None => {
+                println!(
+                    "{:>3}  {:>4}  {:<20}  {:>20}",
+                    record.day, record.part, example, "ERROR"
+                );
+                continue;
+            }
+        };
+        println!(
+            "{:>3}  {:>4}  {:<20}  {:>20}  {:>9.3} ms  {}",
+            record.day,
+            record.part,
+            example,
+            answer,
+            record.time_ms(),
+            record.check.as_deref().unwrap_or("-")
+        );
+    }
+
+    return Ok(all_ok);
+}
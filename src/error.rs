@@ -0,0 +1,115 @@
+//! The error type used by the solution functions and the CLI
+
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// The errors that can occur while loading input or computing a solution
+#[derive(Debug)]
+pub enum AocError {
+    /// Reading or writing a file failed
+    Io { path: PathBuf, source: io::Error },
+
+    /// Fetching a puzzle input failed
+    Fetch { day: u8, reason: String },
+
+    /// A line of the puzzle input could not be parsed
+    Parse {
+        day: u8,
+        line_no: usize,
+        line: String,
+        reason: String,
+    },
+
+    /// The puzzle input was parsed but does not admit a solution
+    NoSolution(String),
+
+    /// The puzzle input as a whole is invalid, e.g. because it is empty
+    InvalidInput(String),
+
+    /// The CLI was invoked with invalid arguments
+    Usage(String),
+
+    /// The solution for a part is a stub that has not been implemented yet
+    NotImplemented { day: u8, part: u8 },
+
+    /// Submitting an answer failed or was refused
+    Submission(String),
+
+    /// A solution did not finish within the time limit
+    Timeout(Duration),
+
+    /// A solution panicked, with the panic message
+    Panic(String),
+}
+
+impl AocError {
+    /// Constructs a parse error; the line number is expected to be 1-based
+    pub fn parse(day: u8, line_no: usize, line: &str, reason: impl ToString) -> AocError {
+        AocError::Parse {
+            day,
+            line_no,
+            line: line.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+
+    /// The exit code the CLI uses for this kind of error
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            AocError::Usage(_) => 2,
+            AocError::Io { .. } | AocError::Fetch { .. } => 3,
+            AocError::Parse { .. } => 4,
+            AocError::InvalidInput(_) => 5,
+            AocError::NoSolution(_) => 6,
+            AocError::NotImplemented { .. } => 7,
+            AocError::Submission(_) => 8,
+            AocError::Timeout(_) => 9,
+            AocError::Panic(_) => 10,
+        }
+    }
+}
+
+impl fmt::Display for AocError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AocError::Io { path, source } => {
+                write!(f, "Failed accessing file {:?}:  {}", path, source)
+            }
+            AocError::Fetch { day, reason } => {
+                write!(f, "Failed fetching input for day {:02}:  {}", day, reason)
+            }
+            AocError::Parse {
+                day,
+                line_no,
+                line,
+                reason,
+            } => write!(
+                f,
+                "Failed parsing input for day {:02} in line {}:  {}\n  {:>4} | {}",
+                day, line_no, reason, line_no, line
+            ),
+            AocError::NoSolution(msg) => write!(f, "No solution found:  {}", msg),
+            AocError::InvalidInput(msg) => write!(f, "Invalid input:  {}", msg),
+            AocError::Usage(msg) => write!(f, "{}", msg),
+            AocError::NotImplemented { day, part } => {
+                write!(f, "Day {:02} part {} is not implemented yet", day, part)
+            }
+            AocError::Submission(msg) => write!(f, "Submission failed:  {}", msg),
+            AocError::Timeout(limit) => {
+                write!(f, "No result within {:.3} s", limit.as_secs_f64())
+            }
+            AocError::Panic(msg) => write!(f, "Solution panicked:  {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for AocError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AocError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
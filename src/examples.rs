@@ -0,0 +1,137 @@
+//! Catalogue of named example inputs per day
+//!
+//! Besides the default test input `dayNN_test.txt`, each day may have a
+//! directory `dayNN/` holding any number of example files `<name>.txt`, e.g.
+//! further examples from the puzzle text or our own edge cases.
+//!
+//! The expected answers of an example are given either in a sidecar file
+//! `<name>.toml` or in a header of lines starting with `#!` at the top of the
+//! example file, both in the syntax of the answers files:
+//!
+//! ```text
+//! #! part1 = 4
+//! #! part2 = 2
+//! 1
+//! 2
+//! ```
+//!
+//! The header is stripped before the input is passed to a solution. If both
+//! are present, the sidecar file takes precedence. The expected answers of
+//! the default test input are those recorded in `answers_test.toml`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::answers::{get_part_answer, Answers, CheckResult};
+use crate::utils::{input_path, read_input};
+use crate::{info, Answer, AocError, Context, InputMode};
+
+/// The name of the default test input `dayNN_test.txt` in the catalogue
+pub const DEFAULT_EXAMPLE: &str = "test";
+
+/// The prefix of the header lines holding the expected answers
+const HEADER_PREFIX: &str = "#!";
+
+/// A named example input along with its expected answers
+pub struct Example {
+    pub name: String,
+    pub path: PathBuf,
+    pub input: String,
+    pub expected: [Option<Answer>; 2],
+}
+
+impl Example {
+    /// Loads an example file, taking the expected answers from its sidecar
+    /// file or its header
+    pub fn load(name: &str, path: &Path) -> Result<Example, AocError> {
+        let content = read_input(path)?;
+
+        // Separate the header from the actual input
+        let mut header = String::new();
+        let mut input = content.as_str();
+        while let Some(line) = input.strip_prefix(HEADER_PREFIX) {
+            let (line, rest) = line.split_once('\n').unwrap_or((line, ""));
+            header.push_str(line);
+            header.push('\n');
+            input = rest;
+        }
+
+        let sidecar_path = path.with_extension("toml");
+        let table = match sidecar_path.exists() {
+            true => parse_table(&read_input(&sidecar_path)?, &sidecar_path)?,
+            false => parse_table(&header, path)?,
+        };
+
+        return Ok(Example {
+            name: name.to_string(),
+            path: path.to_path_buf(),
+            input: input.to_string(),
+            expected: [get_part_answer(&table, 1), get_part_answer(&table, 2)],
+        });
+    }
+
+    /// Compares a solution against the expected answer of the example
+    pub fn check(&self, part: u8, solution: &Answer) -> CheckResult {
+        let expected = match part {
+            1 | 2 => self.expected[part as usize - 1].clone(),
+            _ => None,
+        };
+        return CheckResult::compare(expected, solution);
+    }
+}
+
+/// Parses the expected answers of an example, given in TOML syntax
+fn parse_table(content: &str, path: &Path) -> Result<toml::Table, AocError> {
+    content.parse::<toml::Table>().map_err(|err| {
+        AocError::InvalidInput(format!("Invalid expected answers in {:?}:\n{}", path, err))
+    })
+}
+
+/// Loads all examples for a day, i.e. the default test input, if present,
+/// followed by the examples in the day's directory, sorted by name
+pub fn load_examples(day: u8, ctx: &Context, input_dir: &str) -> Result<Vec<Example>, AocError> {
+    let mut examples = Vec::new();
+
+    let default_path = input_path(day, &InputMode::Test, input_dir);
+    if default_path.exists() {
+        let test_ctx = Context::new(InputMode::Test, ctx.verbosity);
+        let answers = Answers::load(&test_ctx, input_dir)?;
+        examples.push(Example {
+            name: String::from(DEFAULT_EXAMPLE),
+            input: read_input(&default_path)?,
+            path: default_path,
+            expected: [answers.get(day, 1), answers.get(day, 2)],
+        });
+    }
+
+    let dir = Path::new(input_dir).join(format!("day{:02}", day));
+    if !dir.is_dir() {
+        return Ok(examples);
+    }
+
+    info!(ctx, "Loading examples from:\n  {:?}", &dir);
+    let entries = fs::read_dir(&dir).map_err(|source| AocError::Io {
+        path: dir.clone(),
+        source,
+    })?;
+    let mut paths = Vec::new();
+    for entry in entries {
+        let path = entry
+            .map_err(|source| AocError::Io {
+                path: dir.clone(),
+                source,
+            })?
+            .path();
+        if path.extension().is_some_and(|ext| ext == "txt") {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    for path in paths {
+        let name = path.file_stem().unwrap_or_default().to_string_lossy();
+        examples.push(Example::load(&name, &path)?);
+    }
+
+    return Ok(examples);
+}
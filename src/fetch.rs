@@ -0,0 +1,157 @@
+//! Fetching of puzzle inputs, cached in the input directory
+//!
+//! A full input missing from the input directory is fetched via a [`Fetch`]
+//! backend and stored as `dayNN.txt`, such that it is never fetched again.
+//! An empty input file, e.g. a placeholder created when scaffolding a day,
+//! counts as missing.
+//!
+//! The HTTP backend is configured via the environment: `AOC_SESSION` holds
+//! the session token and `AOC_BASE_URL` optionally overrides the base URL,
+//! e.g. to point it to a local stand-in server. Submitting answers uses the
+//! same configuration.
+
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::process;
+use std::sync::Mutex;
+
+use crate::utils::read_input;
+use crate::AocError;
+
+/// The base URL inputs are fetched from by default
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// The base URL configured via `AOC_BASE_URL`, without trailing slash
+pub fn base_url_from_env() -> String {
+    let base_url = env::var("AOC_BASE_URL").unwrap_or(String::from(DEFAULT_BASE_URL));
+    return base_url.trim_end_matches('/').to_string();
+}
+
+/// The session token configured via `AOC_SESSION`, if any
+pub fn session_from_env() -> Option<String> {
+    env::var("AOC_SESSION")
+        .ok()
+        .map(|session| session.trim().to_string())
+        .filter(|session| !session.is_empty())
+}
+
+/// A backend for fetching full puzzle inputs
+pub trait Fetch: fmt::Debug + Send + Sync {
+    /// Fetches the full puzzle input of a day
+    fn fetch(&self, day: u8) -> Result<String, AocError>;
+}
+
+/// Fetches inputs via HTTP, authenticated by a session token
+#[derive(Debug)]
+pub struct HttpFetcher {
+    pub base_url: String,
+    session: Option<String>,
+}
+
+impl HttpFetcher {
+    pub fn new(base_url: &str, session: Option<String>) -> HttpFetcher {
+        HttpFetcher {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session,
+        }
+    }
+
+    /// Configures the fetcher from the `AOC_BASE_URL` and `AOC_SESSION`
+    /// environment variables
+    pub fn from_env() -> HttpFetcher {
+        HttpFetcher::new(&base_url_from_env(), session_from_env())
+    }
+}
+
+impl Fetch for HttpFetcher {
+    fn fetch(&self, day: u8) -> Result<String, AocError> {
+        let session = match &self.session {
+            Some(session) => session,
+            None => {
+                return Err(AocError::Fetch {
+                    day,
+                    reason: String::from("no session token, set AOC_SESSION"),
+                })
+            }
+        };
+
+        let url = format!("{}/2021/day/{}/input", self.base_url, day);
+        let fetch_error = |reason: String| AocError::Fetch { day, reason };
+        let response = ureq::get(&url)
+            .set("Cookie", &format!("session={}", session))
+            .set("User-Agent", "aoc21 input fetcher")
+            .call()
+            .map_err(|err| fetch_error(err.to_string()))?;
+        return response
+            .into_string()
+            .map_err(|err| fetch_error(err.to_string()));
+    }
+}
+
+/// Refuses to fetch anything, for working offline
+#[derive(Debug)]
+pub struct Offline;
+
+impl Fetch for Offline {
+    fn fetch(&self, day: u8) -> Result<String, AocError> {
+        Err(AocError::Fetch {
+            day,
+            reason: String::from("input file is missing and running in offline mode"),
+        })
+    }
+}
+
+/// Serializes fetching, such that solutions running in parallel fetch each
+/// input only once
+static FETCH_LOCK: Mutex<()> = Mutex::new(());
+
+/// Whether an input file is missing or empty, i.e. needs to be fetched
+pub(crate) fn is_missing(filepath: &Path) -> bool {
+    fs::metadata(filepath).map_or(true, |metadata| metadata.len() == 0)
+}
+
+/// Reads an input file, fetching and caching it first if it is missing or
+/// empty
+///
+/// An empty response is reported as an error instead of being cached, since
+/// the empty file would count as missing and be fetched again on every run.
+/// The cache file is written atomically, such that concurrent readers never
+/// see a partially written input.
+pub fn read_or_fetch(filepath: &Path, day: u8, fetcher: &dyn Fetch) -> Result<String, AocError> {
+    if !is_missing(filepath) {
+        return read_input(filepath);
+    }
+
+    // Another thread may have fetched the input while waiting for the lock
+    let _lock = FETCH_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if !is_missing(filepath) {
+        return read_input(filepath);
+    }
+
+    let input = fetcher.fetch(day)?;
+    if input.is_empty() {
+        return Err(AocError::Fetch {
+            day,
+            reason: String::from("received an empty input"),
+        });
+    }
+    let io_error = |source| AocError::Io {
+        path: filepath.to_path_buf(),
+        source,
+    };
+    if let Some(dir) = filepath.parent() {
+        fs::create_dir_all(dir).map_err(io_error)?;
+    }
+    let temp_path = filepath.with_extension(format!("tmp{}", process::id()));
+    fs::write(&temp_path, &input).map_err(io_error)?;
+    fs::rename(&temp_path, filepath).map_err(|source| {
+        fs::remove_file(&temp_path).ok();
+        io_error(source)
+    })?;
+
+    return Ok(input);
+}
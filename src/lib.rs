@@ -0,0 +1,28 @@
+//! Solutions to the Advent of Code 2021 puzzles, usable as a library
+//!
+//! The solution functions are available via the [`get_solution_functions`]
+//! registry or directly from the [`solutions`] modules, which also expose
+//! the parsing functions and domain types of each day.
+
+pub mod answers;
+pub mod bench;
+pub mod driver;
+pub mod error;
+pub mod examples;
+pub mod fetch;
+pub mod parallel;
+pub mod repl;
+pub mod report;
+pub mod scaffold;
+pub mod solutions;
+pub mod status;
+pub mod submit;
+pub mod utils;
+pub mod watch;
+
+pub use answers::Answer;
+pub use error::AocError;
+pub use solutions::{
+    get_solution_functions, get_solutions, Solution, SolutionFunc, StreamFunc, Variant,
+};
+pub use utils::{input_path, load_input, read_input, Context, InputMode, InputSource, Verbosity};
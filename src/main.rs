@@ -1,101 +1,567 @@
 //! CLI for invoking the puzzle solution function for a desired day and part
 
-use std::collections::HashMap;
+mod cli;
+
 use std::env;
 use std::fs;
-use std::path::Path;
+use std::io;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::slice;
+use std::sync::Arc;
+use std::time::Duration;
 
-use utils::InputMode;
+use clap::Parser;
 
-mod solutions;
-mod utils;
+use aoc21::driver::{
+    check_records, print_json, run_all, run_bench, run_test_all, select_solutions, OutputFormat,
+};
+use aoc21::fetch::{base_url_from_env, session_from_env, Fetch, HttpFetcher, Offline};
+use aoc21::repl::Repl;
+use aoc21::report::RunRecord;
+use aoc21::scaffold::{scaffold, DEFAULT_TITLE};
+use aoc21::solutions::day02::{self, Waypoint};
+use aoc21::status::{get_status, PartStatus};
+use aoc21::submit::{parse_wait_time, History, HttpSubmitter, Submit, Verdict};
+use aoc21::watch::FileWatcher;
+use aoc21::{
+    get_solution_functions, get_solutions, info, Answer, AocError, Context, InputMode, InputSource,
+    SolutionFunc,
+};
+use cli::{Cli, Command};
 
-/// Type alias for solution functions
-pub type SolutionFunc = fn(&String, &InputMode) -> i64;
+/// The interval in which watched files are polled for changes
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
 
-/// Constructs a map of all available solution functions
-fn get_solution_functions() -> HashMap<(u8, u8), SolutionFunc> {
-    let mut funcs: HashMap<(u8, u8), SolutionFunc> = HashMap::new();
+/// The environment variable passing the previous answer on to a re-executed
+/// watch process
+const WATCH_PREVIOUS_VAR: &str = "AOC21_WATCH_PREVIOUS";
 
-    funcs.insert((1, 1), solutions::day01::solve_part1);
-    funcs.insert((1, 2), solutions::day01::solve_part2);
+/// Formats the parts with recorded answers, e.g. `1, 2`
+fn format_recorded(recorded: &[bool; 2]) -> String {
+    let parts = (1..=2)
+        .zip(recorded)
+        .filter(|(_, recorded)| **recorded)
+        .map(|(part, _)| part.to_string())
+        .collect::<Vec<_>>();
+    match parts.is_empty() {
+        true => String::from("-"),
+        false => parts.join(", "),
+    }
+}
+
+/// Prints the implementation status of all days as a table, along with the
+/// presence of their inputs and recorded answers
+fn run_list(input_dir: &str) -> Result<(), AocError> {
+    let status = get_status(input_dir)?;
+    let yes_no = |present: bool| match present {
+        true => "yes",
+        false => "-",
+    };
 
-    funcs.insert((2, 1), solutions::day02::solve_part1);
-    funcs.insert((2, 2), solutions::day02::solve_part2);
+    println!(
+        "{:>3}  {:<30}  {:<6}  {:<6}  {:<4}  {:<4}  {:<12}  {:<12}",
+        "Day", "Title", "Part 1", "Part 2", "Test", "Full", "Test answers", "Full answers"
+    );
+    for day in status.iter() {
+        println!(
+            "{:>3}  {:<30}  {:<6}  {:<6}  {:<4}  {:<4}  {:<12}  {}",
+            day.day,
+            day.title.unwrap_or("-"),
+            day.parts[0].to_string(),
+            day.parts[1].to_string(),
+            yes_no(day.test_input),
+            yes_no(day.full_input),
+            format_recorded(&day.test_answers),
+            format_recorded(&day.full_answers)
+        );
+    }
 
-    funcs.insert((3, 1), solutions::day03::solve_part1);
-    funcs.insert((3, 2), solutions::day03::solve_part2);
+    let count = |wanted: PartStatus| {
+        status
+            .iter()
+            .flat_map(|day| day.parts)
+            .filter(|part| *part == wanted)
+            .count()
+    };
+    println!(
+        "\n{} of 25 days with solutions, {} of 50 parts done, {} stub(s)",
+        status.iter().filter(|day| day.title.is_some()).count(),
+        count(PartStatus::Done),
+        count(PartStatus::Stub)
+    );
+    return Ok(());
+}
 
-    funcs.insert((4, 1), solutions::day04::solve_part1);
-    funcs.insert((4, 2), solutions::day04::solve_part2);
+/// Selects the format to export a day 02 trajectory in, i.e. CSV or SVG,
+/// depending on the file extension
+fn trajectory_renderer(path: &Path) -> Result<fn(&[Waypoint]) -> String, AocError> {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("csv") => Ok(day02::trajectory_csv),
+        Some("svg") => Ok(day02::trajectory_svg),
+        _ => Err(AocError::Usage(format!(
+            "Cannot export to {:?}, the file extension should be csv or svg!",
+            path
+        ))),
+    }
+}
 
-    funcs.insert((5, 1), solutions::day05::solve_part1);
-    funcs.insert((5, 2), solutions::day05::solve_part2);
+/// Replays the day 02 course with the submarine model of a part and writes
+/// its trajectory to a file in the given format
+fn export_trajectory(
+    part: u8,
+    ctx: &Context,
+    source: &InputSource,
+    path: &Path,
+    render: fn(&[Waypoint]) -> String,
+) -> Result<(), AocError> {
+    let commands = day02::read_commands(&source.read(2, &ctx.input_mode)?)?;
+    let trajectory = day02::trajectory(&commands, part);
+    fs::write(path, render(&trajectory)).map_err(|source| AocError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    info!(
+        ctx,
+        "Exported trajectory of {} steps to:\n  {:?}",
+        commands.len(),
+        path
+    );
+    return Ok(());
+}
 
-    funcs.insert((6, 1), solutions::day06::solve_part1);
-    funcs.insert((6, 2), solutions::day06::solve_part2);
+/// Submits the answer for a day and part, recording the attempt in the history
+///
+/// Refuses answers known to be wrong, and answers contradicting a recorded
+/// too-high/too-low bound unless `force` is set. Returns whether the answer
+/// was correct.
+fn run_submit(
+    day: u8,
+    part: u8,
+    answer: &Answer,
+    ctx: &Context,
+    input_dir: &str,
+    submitter: &dyn Submit,
+    force: bool,
+) -> Result<bool, AocError> {
+    let history_path = Path::new(input_dir).join("submissions.toml");
+    let mut history = History::load(&history_path)?;
 
-    funcs.insert((7, 1), solutions::day07::solve_part1);
-    funcs.insert((7, 2), solutions::day07::solve_part2);
+    let warnings = history.vet(day, part, answer)?;
+    for warning in warnings.iter() {
+        eprintln!("Warning:  {}", warning);
+    }
+    if !warnings.is_empty() && !force {
+        return Err(AocError::Submission(String::from(
+            "Answer contradicts the recorded bounds, add --force to submit anyway!",
+        )));
+    }
 
-    funcs.insert((8, 1), solutions::day08::solve_part1);
-    funcs.insert((8, 2), solutions::day08::solve_part2);
+    info!(
+        ctx,
+        "Submitting answer {} for day {:02} part {} ...", answer, day, part
+    );
+    let response = submitter.submit(day, part, answer)?;
+    let verdict = Verdict::parse(&response);
+    history.record(day, part, answer, verdict);
+    history.save(&history_path)?;
 
-    return funcs;
+    match (verdict, parse_wait_time(&response)) {
+        (Verdict::RateLimited, Some(wait_time)) => {
+            println!("Verdict:  {} ({} left to wait)", verdict, wait_time)
+        }
+        _ => println!("Verdict:  {}", verdict),
+    }
+    return Ok(verdict == Verdict::Correct);
 }
 
-/// Load the input file as an unprocessed string
-fn load_input(day: u8, _part: u8, input_mode: &InputMode, input_dir: &str) -> String {
-    let cwd = &env::current_dir().unwrap();
-    let dir = Path::new(cwd).join(input_dir);
-    let filepath = match input_mode {
-        InputMode::Test => dir.join(format!("day{:02}_test.txt", day)),
-        InputMode::Full => dir.join(format!("day{:02}.txt", day)),
+/// Rebuilds the CLI and replaces the running process by the new build, passing
+/// on the same arguments and the previous answer; returns if the build fails
+fn rebuild_and_exec(previous: &Option<Answer>) -> Result<(), AocError> {
+    let mut build = process::Command::new("cargo");
+    build.args(["build", "--quiet"]);
+    if !cfg!(debug_assertions) {
+        build.arg("--release");
+    }
+    let status = build.status().map_err(|source| AocError::Io {
+        path: PathBuf::from("cargo"),
+        source,
+    })?;
+    if !status.success() {
+        eprintln!("\nBuild failed, keeping the previous build running.");
+        return Ok(());
+    }
+
+    // The path the process was invoked with refers to the new build, unlike
+    // the path of the running executable, which has been replaced
+    let args = env::args().collect::<Vec<_>>();
+    let mut command = process::Command::new(&args[0]);
+    command.args(&args[1..]);
+    if let Some(answer) = previous {
+        command.env(WATCH_PREVIOUS_VAR, answer.to_string());
+    }
+    let exec_error = |source| AocError::Io {
+        path: PathBuf::from(&args[0]),
+        source,
     };
 
-    println!("Loading input from:\n  {:?}", &filepath);
-    let input = fs::read_to_string(filepath).expect("Failed reading input file!");
-    println!(
-        "Loaded input. (Length: {}, Lines: {})",
-        input.len(),
-        input.matches("\n").count()
-    );
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        return Err(exec_error(command.exec()));
+    }
+    #[cfg(not(unix))]
+    {
+        let status = command.status().map_err(exec_error)?;
+        process::exit(status.code().unwrap_or(1));
+    }
+}
+
+/// Reruns a solution whenever its input files change, clearing the screen and
+/// showing how the answer changed compared to the previous run
+///
+/// Watches the resolved input file as well as all `dayNN*` files in the input
+/// directory. If `watch_src` is set, the day's source file is watched too, and
+/// on changes the CLI is rebuilt and re-executed. Runs until interrupted.
+#[allow(clippy::too_many_arguments)]
+fn run_watch(
+    day: u8,
+    part: u8,
+    func: &SolutionFunc,
+    ctx: &Context,
+    source: &InputSource,
+    input_dir: &str,
+    check: bool,
+    watch_src: bool,
+) -> Result<bool, AocError> {
+    let mut watcher = FileWatcher::new();
+    match source {
+        InputSource::Stdin => {
+            return Err(AocError::Usage(String::from(
+                "Cannot watch input read from stdin!",
+            )))
+        }
+        InputSource::Dir { dir, .. } => watcher.watch_prefix(dir, &format!("day{:02}", day)),
+        InputSource::File(path) => watcher.watch_file(path),
+    }
+    let src_path = PathBuf::from(format!("src/solutions/day{:02}.rs", day));
+    if watch_src {
+        watcher.watch_file(&src_path);
+    }
+
+    let mut previous = env::var(WATCH_PREVIOUS_VAR).ok().map(Answer::Text);
+    loop {
+        // Clear the screen and move the cursor to the top left
+        print!("\x1B[2J\x1B[H");
+        info!(
+            ctx,
+            "---- Day {:02} --- Part {} ---- (watching for changes, Ctrl-C to quit)\n", day, part
+        );
+        let mut record = RunRecord::run(day, part, func, ctx, source);
+        if check && record.error.is_none() {
+            check_records(slice::from_mut(&mut record), ctx, input_dir)?;
+        }
+
+        match (&record.answer, &previous) {
+            (Some(answer), Some(prev)) if answer != prev => {
+                println!(
+                    "The solution is:  {}  (changed, previously {})",
+                    answer, prev
+                )
+            }
+            (Some(answer), Some(_)) => println!("The solution is:  {}  (unchanged)", answer),
+            (Some(answer), None) => println!("The solution is:  {}", answer),
+            (None, _) => {}
+        }
+        if let Some(check_result) = &record.check {
+            println!("Check:  {}", check_result);
+        }
+        if let Some(err) = &record.error {
+            eprintln!("\nError:  {}", err);
+        }
+        if record.answer.is_some() {
+            previous = record.answer.take();
+        }
 
-    return input;
+        let changed = watcher.wait(WATCH_INTERVAL);
+        if watch_src && changed.contains(&src_path) {
+            rebuild_and_exec(&previous)?;
+        }
+    }
 }
 
-/// Provide the CLI for invoking Advent of Code 2021 solution functions
-fn main() {
-    println!("\n--- Advent of Code 2021 ---");
+/// Formats a sorted list of days compactly, e.g. `1-8, 10`
+fn format_days(days: &[u8]) -> String {
+    let mut ranges: Vec<(u8, u8)> = Vec::new();
+    for &day in days.iter() {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == day => *end = day,
+            _ => ranges.push((day, day)),
+        }
+    }
+    return ranges
+        .iter()
+        .map(|(start, end)| match start == end {
+            true => start.to_string(),
+            false => format!("{}-{}", start, end),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+}
 
-    let args: Vec<String> = env::args().collect();
-    if args.len() != 4 {
-        panic!("Invalid number of arguments! Need: [day] [part] [--test/--full].");
+/// Ensures that any of the selected days has a solution, and otherwise
+/// suggests the implemented days as well as how to scaffold a missing one
+fn require_solutions(days: &RangeInclusive<u8>) -> Result<(), AocError> {
+    let implemented = get_solutions()
+        .iter()
+        .map(|solution| solution.day)
+        .collect::<Vec<_>>();
+    if implemented.iter().any(|day| days.contains(day)) {
+        return Ok(());
     }
 
-    let day = args[1].parse::<u8>().unwrap();
-    let part = args[2].parse::<u8>().unwrap();
-    let input_mode = match &args[3] as &str {
-        "--test" => InputMode::Test,
-        "--full" => InputMode::Full,
-        _ => panic!(
-            "Invalid input mode {:?}, should be --test or --full!",
-            &args[3]
+    let mut msg = match days.start() == days.end() {
+        true => format!("Day {} is not implemented yet!", days.start()),
+        false => format!(
+            "None of the days {}..={} is implemented yet!",
+            days.start(),
+            days.end()
         ),
     };
+    msg += &format!(" Implemented days: {}.", format_days(&implemented));
+    if days.start() == days.end() {
+        msg += &format!(" Use `aoc21 new {}` to scaffold it.", days.start());
+    }
+    return Err(AocError::Usage(msg));
+}
+
+/// Returns the day of a selection which must consist of a single day, e.g.
+/// because a part is given as well
+fn single_day(days: &RangeInclusive<u8>) -> Result<u8, AocError> {
+    match days.start() == days.end() {
+        true => Ok(*days.start()),
+        false => Err(AocError::Usage(String::from(
+            "A part can only be given for a single day!",
+        ))),
+    }
+}
+
+/// Looks up the solution function for a day and part
+fn solution_func(day: u8, part: u8) -> Result<SolutionFunc, AocError> {
+    require_solutions(&(day..=day))?;
+    match get_solution_functions().get(&(day, part)) {
+        Some(func) => Ok(*func),
+        None => Err(AocError::Usage(format!(
+            "No solution function registered for day {} part {}!",
+            day, part
+        ))),
+    }
+}
+
+/// Runs the selected subcommand
+///
+/// Returns whether all solutions were computed and passed the checks.
+fn run(cli: Cli) -> Result<bool, AocError> {
+    let verbosity = cli.global.verbosity();
     let input_dir = "input";
+    let fetcher: Arc<dyn Fetch> = match cli.global.offline {
+        true => Arc::new(Offline),
+        false => Arc::new(HttpFetcher::from_env()),
+    };
+    let dir_source = InputSource::dir(input_dir, fetcher);
+
+    match cli.command {
+        Command::Run(args) => {
+            require_solutions(&args.days)?;
+            if args.part.is_some() {
+                single_day(&args.days)?;
+            }
+            if args.window.is_some() && args.days != (1..=1) {
+                return Err(AocError::Usage(String::from(
+                    "A window size can only be given for day 1!",
+                )));
+            }
+            if args.export.is_some() && args.days != (2..=2) {
+                return Err(AocError::Usage(String::from(
+                    "A trajectory can only be exported for day 2!",
+                )));
+            }
+            let export = match &args.export {
+                Some(_) if matches!(args.input, Some(InputSource::Stdin)) => {
+                    return Err(AocError::Usage(String::from(
+                        "Cannot export the trajectory of input read from stdin!",
+                    )))
+                }
+                Some(path) => Some((path, trajectory_renderer(path)?)),
+                None => None,
+            };
+            let ctx = Context::new(args.input_mode.input_mode(), verbosity)
+                .with_window(args.window.map(usize::from));
+            info!(ctx, "\n--- Advent of Code 2021 ---");
+            let part = match args.part {
+                Some(part) => part,
+                None => {
+                    let (check, format) = (args.check, args.format);
+                    return run_all(
+                        args.days,
+                        &ctx,
+                        &dir_source,
+                        input_dir,
+                        check,
+                        format,
+                        args.pool.jobs as usize,
+                        args.pool.timeout,
+                    );
+                }
+            };
+
+            let day = single_day(&args.days)?;
+            let func = solution_func(day, part)?;
+            let source = args.input.unwrap_or(dir_source);
+            if args.watch || args.watch_src {
+                return run_watch(
+                    day,
+                    part,
+                    &func,
+                    &ctx,
+                    &source,
+                    input_dir,
+                    args.check,
+                    args.watch_src,
+                );
+            }
 
-    println!("---- Day {:02} --- Part {} ----\n", day, part);
-    let input = load_input(day, part, &input_mode, &input_dir);
+            info!(ctx, "---- Day {:02} --- Part {} ----\n", day, part);
+            let mut record = RunRecord::run(day, part, &func, &ctx, &source);
+            let mut all_passed = true;
+            if args.check && record.error.is_none() {
+                all_passed = check_records(slice::from_mut(&mut record), &ctx, input_dir)?;
+            }
 
-    // Retrieve and invoke solution function
-    let solution = match get_solution_functions().get(&(day, part)) {
-        Some(func) => {
-            println!("\nNow computing solution ...");
-            func(&input, &input_mode)
+            match args.format {
+                OutputFormat::Json => print_json(&record),
+                OutputFormat::Text => {
+                    if let Some(answer) = &record.answer {
+                        println!("The solution is:  {}", answer);
+                    }
+                    if let Some(check_result) = &record.check {
+                        println!("Check:  {}", check_result);
+                    }
+                }
+            }
+
+            if let Some(err) = record.error.take() {
+                return Err(err);
+            }
+            if let Some((path, render)) = export {
+                export_trajectory(part, &ctx, &source, path, render)?;
+            }
+            return Ok(all_passed);
+        }
+
+        Command::Check(args) => {
+            require_solutions(&args.days)?;
+            if args.examples {
+                return run_test_all(args.days, verbosity, input_dir, args.format);
+            }
+            let ctx = Context::new(args.input_mode.input_mode(), verbosity);
+            info!(ctx, "\n--- Advent of Code 2021 ---");
+            return run_all(
+                args.days,
+                &ctx,
+                &dir_source,
+                input_dir,
+                true,
+                args.format,
+                args.pool.jobs as usize,
+                args.pool.timeout,
+            );
+        }
+
+        Command::Bench(args) => {
+            require_solutions(&args.days)?;
+            let ctx = Context::new(args.input_mode.input_mode(), verbosity);
+            let keys = match args.part {
+                Some(part) => {
+                    let day = single_day(&args.days)?;
+                    solution_func(day, part)?;
+                    vec![(day, part)]
+                }
+                None => select_solutions(&args.days),
+            };
+            let source = args.input.unwrap_or(dir_source);
+            info!(ctx, "\n--- Advent of Code 2021 ---");
+            return run_bench(&keys, &ctx, &source, args.runs as usize, args.variants);
+        }
+
+        Command::List => {
+            run_list(input_dir)?;
+            return Ok(true);
+        }
+
+        Command::New { day, title } => {
+            let title = match title.is_empty() {
+                true => String::from(DEFAULT_TITLE),
+                false => title.join(" "),
+            };
+            for path in scaffold(day, &title, "src", input_dir)? {
+                println!("Created {:?}", path);
+            }
+            return Ok(true);
         }
-        None => panic!("No solution function registered for this day or part!"),
-    };
 
-    println!("The solution is:  {}", solution);
+        // The answer is computed from the full input if not given
+        Command::Submit(args) => {
+            let (day, part) = (args.day, args.part);
+            let ctx = Context::new(InputMode::Full, verbosity);
+            let answer = match args.answer {
+                Some(answer) => match answer.parse::<i64>() {
+                    Ok(value) => Answer::Int(value),
+                    Err(_) => Answer::Text(answer),
+                },
+                None => {
+                    let func = solution_func(day, part)?;
+                    let source = args.input.unwrap_or(dir_source);
+                    let mut record = RunRecord::run(day, part, &func, &ctx, &source);
+                    match (record.answer.take(), record.error.take()) {
+                        (Some(answer), _) => answer,
+                        (None, Some(err)) => return Err(err),
+                        (None, None) => unreachable!("Run without answer or error!"),
+                    }
+                }
+            };
+            let submitter: Box<dyn Submit> = match cli.global.offline {
+                true => Box::new(Offline),
+                false => Box::new(HttpSubmitter::new(&base_url_from_env(), session_from_env())),
+            };
+            return run_submit(
+                day,
+                part,
+                &answer,
+                &ctx,
+                input_dir,
+                submitter.as_ref(),
+                args.force,
+            );
+        }
+
+        Command::Repl => {
+            println!("--- Advent of Code 2021 ---  (type `help` for a list of commands)");
+            Repl::new(input_dir, dir_source, verbosity).run(io::stdin().lock())?;
+            return Ok(true);
+        }
+    }
+}
+
+/// Provide the CLI for invoking Advent of Code 2021 solution functions
+fn main() {
+    match run(Cli::parse()) {
+        Ok(true) => {}
+        Ok(false) => process::exit(1),
+        Err(err) => {
+            eprintln!("\nError:  {}", err);
+            process::exit(err.exit_code());
+        }
+    }
 }
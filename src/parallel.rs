@@ -0,0 +1,100 @@
+//! Parallel execution of solutions with timeouts and panic isolation
+//!
+//! Each solution runs on a thread of its own, such that a panic is caught and
+//! reported as an error, and such that the run can be given up after a time
+//! limit. A solution which timed out keeps running in the background until
+//! the process exits, since threads cannot be cancelled.
+
+use std::any::Any;
+use std::collections::{HashMap, VecDeque};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use crate::report::RunRecord;
+use crate::{info, AocError, Context, InputSource, SolutionFunc};
+
+/// Extracts the message from the payload of a panic
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(msg) = payload.downcast_ref::<&str>() {
+        return msg.to_string();
+    }
+    match payload.downcast::<String>() {
+        Ok(msg) => *msg,
+        Err(_) => String::from("unknown panic payload"),
+    }
+}
+
+/// Runs a solution on a thread of its own, catching panics and giving up
+/// after the timeout, if any
+pub fn run_isolated(
+    day: u8,
+    part: u8,
+    func: SolutionFunc,
+    ctx: &Context,
+    source: &InputSource,
+    timeout: Option<Duration>,
+) -> RunRecord {
+    let (sender, receiver) = mpsc::channel();
+    let (thread_ctx, thread_source) = (*ctx, source.clone());
+    thread::spawn(move || {
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            RunRecord::run(day, part, &func, &thread_ctx, &thread_source)
+        }));
+        sender.send(result).ok();
+    });
+
+    let result = match timeout {
+        Some(timeout) => receiver.recv_timeout(timeout),
+        None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+    };
+    let error = match result {
+        Ok(Ok(record)) => return record,
+        Ok(Err(payload)) => AocError::Panic(panic_message(payload)),
+        Err(RecvTimeoutError::Timeout) => AocError::Timeout(timeout.unwrap_or_default()),
+        Err(RecvTimeoutError::Disconnected) => {
+            AocError::Panic(String::from("solution thread terminated"))
+        }
+    };
+    return RunRecord::failed(day, part, ctx, source.path(day, &ctx.input_mode), error);
+}
+
+/// Runs the solutions for the given keys on a pool of `jobs` worker threads
+///
+/// Every solution is run isolated, see [`run_isolated`]. The records are
+/// returned in the order of the keys, regardless of the order of completion.
+pub fn run_parallel(
+    keys: &[(u8, u8)],
+    funcs: &HashMap<(u8, u8), SolutionFunc>,
+    ctx: &Context,
+    source: &InputSource,
+    jobs: usize,
+    timeout: Option<Duration>,
+) -> Vec<RunRecord> {
+    let queue = Mutex::new(keys.iter().copied().enumerate().collect::<VecDeque<_>>());
+    let results = Mutex::new(Vec::with_capacity(keys.len()));
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, keys.len().max(1)) {
+            scope.spawn(|| loop {
+                let next = queue.lock().expect("Job queue poisoned!").pop_front();
+                let (index, (day, part)) = match next {
+                    Some(job) => job,
+                    None => break,
+                };
+                info!(ctx, "\n---- Day {:02} --- Part {} ----\n", day, part);
+                let record = run_isolated(day, part, funcs[&(day, part)], ctx, source, timeout);
+                results
+                    .lock()
+                    .expect("Results poisoned!")
+                    .push((index, record));
+            });
+        }
+    });
+
+    let mut results = results.into_inner().expect("Results poisoned!");
+    results.sort_by_key(|(index, _)| *index);
+    return results.into_iter().map(|(_, record)| record).collect();
+}
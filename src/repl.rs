@@ -0,0 +1,282 @@
+//! Interactive shell for exploring the solutions of a day
+//!
+//! Keeps the registry and the loaded input in memory, such that a solution
+//! can be re-run with edited inputs or a different verbosity without
+//! restarting the process.
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+
+use crate::answers::{Answers, CheckResult};
+use crate::examples::load_examples;
+use crate::report::RunRecord;
+use crate::utils::read_input;
+use crate::{
+    get_solution_functions, get_solutions, Answer, AocError, Context, InputMode, InputSource,
+    SolutionFunc, Verbosity,
+};
+
+/// The prompt shown before reading a command
+const PROMPT: &str = "aoc21> ";
+
+/// The help text listing the available commands
+pub const HELP: &str = "Commands:
+  load <day> [test|full|<example>]  load the input of a day (default: test)
+  input <path>                      load the input of the current day from a file
+  reload                            re-read the current input from disk
+  run [part]                        run a part, or both parts if none is given
+  time [on|off]                     show the parse and solve times of runs
+  verbose [quiet|on|off|debug]      set the verbosity of the solutions
+  help                              show this help
+  quit                              leave the shell";
+
+/// Where the currently loaded input comes from
+#[derive(Clone)]
+enum InputSpec {
+    /// The default input file for an input mode
+    Default(InputMode),
+    /// A named example from the day's catalogue
+    Example(String),
+    /// An arbitrary file
+    File(PathBuf),
+}
+
+/// The currently loaded input along with its expected answers, if known
+struct Loaded {
+    day: u8,
+    spec: InputSpec,
+    input_mode: InputMode,
+    path: PathBuf,
+    input: String,
+    expected: [Option<Answer>; 2],
+}
+
+/// The state of the interactive shell
+pub struct Repl {
+    input_dir: String,
+    source: InputSource,
+    funcs: HashMap<(u8, u8), SolutionFunc>,
+    verbosity: Verbosity,
+    timing: bool,
+    loaded: Option<Loaded>,
+}
+
+impl Repl {
+    /// Constructs a shell loading default inputs from the given source
+    pub fn new(input_dir: &str, source: InputSource, verbosity: Verbosity) -> Repl {
+        Repl {
+            input_dir: input_dir.to_string(),
+            source,
+            funcs: get_solution_functions(),
+            verbosity,
+            timing: false,
+            loaded: None,
+        }
+    }
+
+    /// Reads and executes commands until the input ends or `quit` is given
+    ///
+    /// Errors of single commands are reported without leaving the shell.
+    pub fn run<R: BufRead>(&mut self, reader: R) -> Result<(), AocError> {
+        let mut lines = reader.lines();
+        loop {
+            print!("{}", PROMPT);
+            io::stdout().flush().ok();
+
+            let line = match lines.next() {
+                Some(line) => line.map_err(|source| AocError::Io {
+                    path: PathBuf::from("<stdin>"),
+                    source,
+                })?,
+                None => {
+                    println!();
+                    return Ok(());
+                }
+            };
+            match self.execute(&line) {
+                Ok(true) => {}
+                Ok(false) => return Ok(()),
+                Err(err) => eprintln!("Error:  {}", err),
+            }
+        }
+    }
+
+    /// Executes a single command; returns whether to continue afterwards
+    pub fn execute(&mut self, line: &str) -> Result<bool, AocError> {
+        let words = line.split_whitespace().collect::<Vec<_>>();
+        match words.as_slice() {
+            [] => {}
+            ["quit"] | ["exit"] => return Ok(false),
+            ["help"] => println!("{}", HELP),
+            ["load", day] => {
+                self.load(parse_arg(day, "day")?, InputSpec::Default(InputMode::Test))?
+            }
+            ["load", day, name] => {
+                let spec = match *name {
+                    "test" => InputSpec::Default(InputMode::Test),
+                    "full" => InputSpec::Default(InputMode::Full),
+                    name => InputSpec::Example(name.to_string()),
+                };
+                self.load(parse_arg(day, "day")?, spec)?;
+            }
+            ["input", path] => {
+                let day = self.loaded()?.day;
+                self.load(day, InputSpec::File(PathBuf::from(path)))?;
+            }
+            ["reload"] => {
+                let loaded = self.loaded()?;
+                self.load(loaded.day, loaded.spec.clone())?;
+            }
+            ["run"] => {
+                self.run_part(1)?;
+                self.run_part(2)?;
+            }
+            ["run", part] => self.run_part(parse_arg(part, "part")?)?,
+            ["time"] => self.timing = !self.timing,
+            ["time", "on"] => self.timing = true,
+            ["time", "off"] => self.timing = false,
+            ["verbose"] | ["verbose", "on"] => self.verbosity = Verbosity::Verbose,
+            ["verbose", "off"] => self.verbosity = Verbosity::Normal,
+            ["verbose", "quiet"] => self.verbosity = Verbosity::Quiet,
+            ["verbose", "debug"] => self.verbosity = Verbosity::Debug,
+            _ => {
+                return Err(AocError::Usage(format!(
+                    "Invalid command {:?}, type `help` for a list of commands!",
+                    line.trim()
+                )))
+            }
+        }
+        return Ok(true);
+    }
+
+    /// The currently loaded input, if any
+    fn loaded(&self) -> Result<&Loaded, AocError> {
+        self.loaded.as_ref().ok_or(AocError::Usage(String::from(
+            "No input loaded yet, use `load <day>` first!",
+        )))
+    }
+
+    /// Loads the input of a day from the given place
+    fn load(&mut self, day: u8, spec: InputSpec) -> Result<(), AocError> {
+        let quiet_ctx = |input_mode| Context::new(input_mode, Verbosity::Quiet);
+        let loaded = match &spec {
+            InputSpec::Default(input_mode) => {
+                let answers = Answers::load(&quiet_ctx(*input_mode), &self.input_dir)?;
+                Loaded {
+                    day,
+                    spec: spec.clone(),
+                    input_mode: *input_mode,
+                    path: self.source.path(day, input_mode),
+                    input: self.source.read(day, input_mode)?,
+                    expected: [answers.get(day, 1), answers.get(day, 2)],
+                }
+            }
+            InputSpec::Example(name) => {
+                let examples = load_examples(day, &quiet_ctx(InputMode::Test), &self.input_dir)?;
+                let names = examples
+                    .iter()
+                    .map(|ex| ex.name.clone())
+                    .collect::<Vec<_>>();
+                let example = match examples.into_iter().find(|ex| ex.name == *name) {
+                    Some(example) => example,
+                    None => {
+                        return Err(AocError::Usage(format!(
+                            "No example {:?} for day {:02}, available: {}",
+                            name,
+                            day,
+                            names.join(", ")
+                        )))
+                    }
+                };
+                Loaded {
+                    day,
+                    spec: spec.clone(),
+                    input_mode: InputMode::Test,
+                    path: example.path,
+                    input: example.input,
+                    expected: example.expected,
+                }
+            }
+            InputSpec::File(path) => Loaded {
+                day,
+                spec: spec.clone(),
+                input_mode: self
+                    .loaded
+                    .as_ref()
+                    .map_or(InputMode::Test, |l| l.input_mode),
+                path: path.clone(),
+                input: read_input(path)?,
+                expected: [None, None],
+            },
+        };
+
+        let title = get_solutions()
+            .into_iter()
+            .find(|solution| solution.day == day)
+            .map_or("", |solution| solution.title);
+        println!(
+            "Loaded day {:02} - {} from {:?} (Length: {}, Lines: {})",
+            day,
+            title,
+            loaded.path,
+            loaded.input.len(),
+            loaded.input.matches("\n").count()
+        );
+        self.loaded = Some(loaded);
+        return Ok(());
+    }
+
+    /// Runs a part on the loaded input and prints the answer
+    fn run_part(&self, part: u8) -> Result<(), AocError> {
+        let loaded = self.loaded()?;
+        let func = match self.funcs.get(&(loaded.day, part)) {
+            Some(func) => func,
+            None => {
+                return Err(AocError::Usage(format!(
+                    "No solution function registered for day {} part {}!",
+                    loaded.day, part
+                )))
+            }
+        };
+
+        let ctx = Context::new(loaded.input_mode, self.verbosity);
+        let mut record = RunRecord::run_input(
+            loaded.day,
+            part,
+            func,
+            &ctx,
+            loaded.path.clone(),
+            &loaded.input,
+        );
+        if let Some(err) = record.error.take() {
+            return Err(err);
+        }
+
+        let answer = record
+            .answer
+            .as_ref()
+            .expect("Run without answer or error!");
+        let expected = loaded.expected.get(part as usize - 1).cloned().flatten();
+        let mut line = format!("Part {}:  {}", part, answer);
+        match CheckResult::compare(expected, answer) {
+            CheckResult::Unknown => {}
+            check_result => line += &format!("  [{}]", check_result),
+        }
+        if self.timing {
+            line += &format!(
+                "  (parse: {:.3} ms, solve: {:.3} ms)",
+                record.parse_time_ms.unwrap_or(0.),
+                record.solve_time_ms.unwrap_or(0.)
+            );
+        }
+        println!("{}", line);
+        return Ok(());
+    }
+}
+
+/// Parses a day or part number argument of a command
+fn parse_arg(arg: &str, what: &str) -> Result<u8, AocError> {
+    arg.parse::<u8>()
+        .map_err(|_| AocError::Usage(format!("Invalid {} {:?}, should be a number!", what, arg)))
+}
@@ -0,0 +1,223 @@
+//! Records of solution runs, e.g. for machine-readable output
+
+use std::io::{self, BufRead, Read};
+use std::path::PathBuf;
+use std::time::Instant;
+
+use serde::{Serialize, Serializer};
+
+use crate::{
+    info, load_input, Answer, AocError, Context, InputMode, InputSource, SolutionFunc, StreamFunc,
+};
+
+/// The record of running a single solution function on its input
+///
+/// Serializes to a flat JSON object; durations are given in milliseconds and
+/// the error, if any, as its message. Input read from stdin has the path `-`.
+#[derive(Serialize)]
+pub struct RunRecord {
+    pub day: u8,
+    pub part: u8,
+    pub input_mode: InputMode,
+    pub input_path: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub example: Option<String>,
+    pub input_length: Option<usize>,
+    pub input_lines: Option<usize>,
+    pub answer: Option<Answer>,
+    pub parse_time_ms: Option<f64>,
+    pub solve_time_ms: Option<f64>,
+    #[serde(serialize_with = "serialize_error")]
+    pub error: Option<AocError>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub check: Option<String>,
+}
+
+/// Serializes an error via its message
+fn serialize_error<S: Serializer>(error: &Option<AocError>, s: S) -> Result<S::Ok, S::Error> {
+    match error {
+        Some(err) => s.serialize_some(&err.to_string()),
+        None => s.serialize_none(),
+    }
+}
+
+impl RunRecord {
+    /// Loads the input for the given day, runs the solution function on it,
+    /// and records the outcome, including any error that occurred
+    ///
+    /// Solutions which support it are run on the input streamed line by line
+    /// instead, see [`RunRecord::run_stream`].
+    pub fn run(
+        day: u8,
+        part: u8,
+        func: &SolutionFunc,
+        ctx: &Context,
+        source: &InputSource,
+    ) -> RunRecord {
+        if let Some(stream) = func.stream {
+            return RunRecord::run_stream(day, part, stream, ctx, source);
+        }
+        let input_path = source.path(day, &ctx.input_mode);
+        match load_input(day, part, ctx, source) {
+            Ok(input) => RunRecord::run_input(day, part, func, ctx, input_path, &input),
+            Err(err) => RunRecord::failed(day, part, ctx, input_path, err),
+        }
+    }
+
+    /// Runs the solution function on an already loaded input and records the
+    /// outcome, including any error that occurred
+    pub fn run_input(
+        day: u8,
+        part: u8,
+        func: &SolutionFunc,
+        ctx: &Context,
+        input_path: PathBuf,
+        input: &String,
+    ) -> RunRecord {
+        let mut record = RunRecord::new(day, part, ctx, input_path);
+        record.input_length = Some(input.len());
+        record.input_lines = Some(input.matches("\n").count());
+
+        info!(ctx, "\nNow computing solution ...");
+        let start = Instant::now();
+        let parsed = match (func.parse)(input) {
+            Ok(parsed) => parsed,
+            Err(err) => {
+                record.error = Some(err);
+                return record;
+            }
+        };
+        record.parse_time_ms = Some(start.elapsed().as_secs_f64() * 1000.);
+
+        let start = Instant::now();
+        match (func.solve)(parsed.as_ref(), ctx) {
+            Ok(answer) => record.answer = Some(answer),
+            Err(err) => record.error = Some(err),
+        }
+        record.solve_time_ms = Some(start.elapsed().as_secs_f64() * 1000.);
+
+        return record;
+    }
+
+    /// Runs a streaming solution function on the input read line by line and
+    /// records the outcome, including any error that occurred
+    ///
+    /// As the input is never held in memory, its length and number of lines
+    /// are counted while it passes through, and parsing is timed as part of
+    /// solving.
+    pub fn run_stream(
+        day: u8,
+        part: u8,
+        stream: StreamFunc,
+        ctx: &Context,
+        source: &InputSource,
+    ) -> RunRecord {
+        let input_path = source.path(day, &ctx.input_mode);
+        let mut reader = match source.open(day, &ctx.input_mode) {
+            Ok(reader) => reader,
+            Err(err) => return RunRecord::failed(day, part, ctx, input_path, err),
+        };
+        let mut record = RunRecord::new(day, part, ctx, input_path);
+
+        info!(
+            ctx,
+            "Streaming input from:\n  {:?}\n\nNow computing solution ...", record.input_path
+        );
+        let start = Instant::now();
+        let mut counter = CountingReader::new(reader.as_mut());
+        match stream(&mut counter, ctx) {
+            Ok(answer) => record.answer = Some(answer),
+            Err(err) => record.error = Some(err),
+        }
+        record.solve_time_ms = Some(start.elapsed().as_secs_f64() * 1000.);
+        record.input_length = Some(counter.length);
+        record.input_lines = Some(counter.lines);
+
+        return record;
+    }
+
+    /// Constructs the record of a run which failed with an error
+    pub fn failed(
+        day: u8,
+        part: u8,
+        ctx: &Context,
+        input_path: PathBuf,
+        error: AocError,
+    ) -> RunRecord {
+        let mut record = RunRecord::new(day, part, ctx, input_path);
+        record.error = Some(error);
+        return record;
+    }
+
+    /// Constructs a record without any outcome yet
+    fn new(day: u8, part: u8, ctx: &Context, input_path: PathBuf) -> RunRecord {
+        RunRecord {
+            day,
+            part,
+            input_mode: ctx.input_mode,
+            input_path,
+            example: None,
+            input_length: None,
+            input_lines: None,
+            answer: None,
+            parse_time_ms: None,
+            solve_time_ms: None,
+            error: None,
+            check: None,
+        }
+    }
+
+    /// The total run time of the parsing and solving phases, in milliseconds
+    pub fn time_ms(&self) -> f64 {
+        self.parse_time_ms.unwrap_or(0.) + self.solve_time_ms.unwrap_or(0.)
+    }
+}
+
+// -----------------------------------------------------------------------------
+
+/// A reader counting the bytes and newlines consumed from the inner reader
+struct CountingReader<'a> {
+    inner: &'a mut dyn BufRead,
+    length: usize,
+    lines: usize,
+}
+
+impl<'a> CountingReader<'a> {
+    fn new(inner: &'a mut dyn BufRead) -> CountingReader<'a> {
+        CountingReader {
+            inner,
+            length: 0,
+            lines: 0,
+        }
+    }
+}
+
+/// Counts the newlines in a chunk of input
+fn count_newlines(bytes: &[u8]) -> usize {
+    bytes.iter().filter(|&&byte| byte == b'\n').count()
+}
+
+impl Read for CountingReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let num_read = self.inner.read(buf)?;
+        self.length += num_read;
+        self.lines += count_newlines(&buf[..num_read]);
+        return Ok(num_read);
+    }
+}
+
+impl BufRead for CountingReader<'_> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        // The buffer is still filled from the preceding call to `fill_buf`
+        if let Ok(buf) = self.inner.fill_buf() {
+            let consumed = &buf[..amt.min(buf.len())];
+            self.length += consumed.len();
+            self.lines += count_newlines(consumed);
+        }
+        self.inner.consume(amt);
+    }
+}
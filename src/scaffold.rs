@@ -0,0 +1,143 @@
+//! Scaffolding of the files for a new day
+//!
+//! Generates the solution module with the established doc header and stubs
+//! for both parts, as well as empty input files. The solution is registered
+//! automatically by the build script once the module exists.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::AocError;
+
+/// The title used if none is given
+pub const DEFAULT_TITLE: &str = "Untitled";
+
+/// The template of a solution module; `%DD%` is replaced by the zero-padded
+/// day, `%D%` by the day, and `%TITLE%` by the puzzle title
+const MODULE_TEMPLATE: &str = r#"//! # Solutions for Day %DD% - %TITLE%
+//!
+//! For puzzle text, see https://adventofcode.com/2021/day/%D%
+
+use crate::{Answer, AocError, Context, Solution};
+
+const DAY: u8 = %D%;
+
+/// Splits the input into its lines
+pub fn read_lines(input: &String) -> Result<Vec<String>, AocError> {
+    return Ok(input.lines().map(String::from).collect());
+}
+
+// -----------------------------------------------------------------------------
+
+/// Implements the solution for part 1
+pub fn solve_part1(_lines: &Vec<String>, _ctx: &Context) -> Result<Answer, AocError> {
+    return Err(AocError::NotImplemented { day: DAY, part: 1 });
+}
+
+/// Implements the solution for part 2
+pub fn solve_part2(_lines: &Vec<String>, _ctx: &Context) -> Result<Answer, AocError> {
+    return Err(AocError::NotImplemented { day: DAY, part: 2 });
+}
+
+// -----------------------------------------------------------------------------
+
+/// The solution for day %DD%
+pub struct Day%DD%;
+
+impl Solution for Day%DD% {
+    const DAY: u8 = DAY;
+    // Set a part to `true` once it is implemented, see the `list` command
+    const IMPLEMENTED: [bool; 2] = [false, false];
+    type Input = Vec<String>;
+
+    fn parse(input: &String) -> Result<Vec<String>, AocError> {
+        read_lines(input)
+    }
+
+    fn part1(input: &Vec<String>, ctx: &Context) -> Result<Answer, AocError> {
+        solve_part1(input, ctx)
+    }
+
+    fn part2(input: &Vec<String>, ctx: &Context) -> Result<Answer, AocError> {
+        solve_part2(input, ctx)
+    }
+}
+"#;
+
+/// Generates the solution module and the empty input files for a day
+///
+/// The module is placed in `<src_dir>/solutions/`, the input files in the
+/// input directory, which is created if needed. Refuses to overwrite any
+/// existing file, in which case no file is created at all; if creating a file
+/// fails, the files created before are removed again. Returns the paths of
+/// the created files.
+pub fn scaffold(
+    day: u8,
+    title: &str,
+    src_dir: &str,
+    input_dir: &str,
+) -> Result<Vec<PathBuf>, AocError> {
+    if !(1..=25).contains(&day) {
+        return Err(AocError::Usage(format!(
+            "Invalid day {}, should be between 1 and 25!",
+            day
+        )));
+    }
+    if title.contains('"') || title.contains('\\') || title.contains('\n') {
+        return Err(AocError::Usage(format!("Invalid title {:?}!", title)));
+    }
+
+    let module = MODULE_TEMPLATE
+        .replace("%DD%", &format!("{:02}", day))
+        .replace("%D%", &day.to_string())
+        .replace("%TITLE%", title);
+    let files = [
+        (
+            Path::new(src_dir)
+                .join("solutions")
+                .join(format!("day{:02}.rs", day)),
+            module,
+        ),
+        (
+            Path::new(input_dir).join(format!("day{:02}.txt", day)),
+            String::new(),
+        ),
+        (
+            Path::new(input_dir).join(format!("day{:02}_test.txt", day)),
+            String::new(),
+        ),
+    ];
+
+    if let Some((path, _)) = files.iter().find(|(path, _)| path.exists()) {
+        return Err(AocError::Usage(format!(
+            "Refusing to overwrite existing file {:?}!",
+            path
+        )));
+    }
+
+    fs::create_dir_all(input_dir).map_err(|source| AocError::Io {
+        path: PathBuf::from(input_dir),
+        source,
+    })?;
+
+    let mut created = Vec::new();
+    for (path, content) in files {
+        let result = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .and_then(|mut file| {
+                created.push(path.clone());
+                file.write_all(content.as_bytes())
+            });
+        if let Err(source) = result {
+            for path in created.iter() {
+                fs::remove_file(path).ok();
+            }
+            return Err(AocError::Io { path, source });
+        }
+    }
+
+    return Ok(created);
+}
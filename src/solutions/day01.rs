@@ -2,58 +2,245 @@
 //!
 //! For puzzle text, see https://adventofcode.com/2021/day/1
 
-use crate::InputMode;
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::BufRead;
+
+use crate::solutions::stream_lines;
+use crate::{debug, verbose, Answer, AocError, Context, Solution, StreamFunc};
+
+const DAY: u8 = 1;
+
+/// Parses a single depth measurement; expects a 0-based line number
+pub fn parse_depth((line_no, line): (usize, &str)) -> Result<i64, AocError> {
+    line.trim()
+        .parse::<i64>()
+        .map_err(|err| AocError::parse(DAY, line_no + 1, line, err))
+}
+
+/// Parses the input into the series of depth measurements
+pub fn read_depths(input: &String) -> Result<Vec<i64>, AocError> {
+    input.lines().enumerate().map(parse_depth).collect()
+}
+
+/// The window size of part 1, i.e. comparing single measurements
+pub const PART1_WINDOW: usize = 1;
+
+/// The window size of part 2, i.e. comparing three-measurement sums
+pub const PART2_WINDOW: usize = 3;
+
+/// A single window of the sweep, with the change of its sum compared to the
+/// previous window
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WindowStep {
+    pub start: usize,
+    pub sum: i64,
+    pub change: Option<i64>,
+}
+
+/// The analysis of a depth series with a sliding window of a certain size
+#[derive(Clone, Debug)]
+pub struct SonarReport {
+    pub window: usize,
+    /// The number of windows whose sum is larger than the previous one
+    pub num_increasing: usize,
+    /// The largest number of consecutive increases
+    pub longest_increasing_run: usize,
+    /// The largest increase from one window to the next, if any
+    pub largest_increase: Option<i64>,
+    pub trace: Vec<WindowStep>,
+}
+
+/// Slides a window over depths given one at a time, keeping only the depths
+/// of the current window in memory
+pub struct WindowTracker {
+    depths: VecDeque<i64>,
+    sum: i64,
+    num_windows: usize,
+    run: usize,
+    report: SonarReport,
+}
+
+impl WindowTracker {
+    pub fn new(window: usize) -> Result<WindowTracker, AocError> {
+        if window == 0 {
+            return Err(AocError::Usage(String::from(
+                "The window size should be positive!",
+            )));
+        }
+        return Ok(WindowTracker {
+            depths: VecDeque::with_capacity(window + 1),
+            sum: 0,
+            num_windows: 0,
+            run: 0,
+            report: SonarReport {
+                window,
+                num_increasing: 0,
+                longest_increasing_run: 0,
+                largest_increase: None,
+                trace: Vec::new(),
+            },
+        });
+    }
+
+    /// The depths of the current window
+    pub fn depths(&self) -> &VecDeque<i64> {
+        &self.depths
+    }
+
+    /// Adds the next depth, returning the resulting window once it is full
+    ///
+    /// Consecutive windows share all but one depth each, so the change of the
+    /// sum is the difference between the added and the dropped depth, i.e.
+    /// the sum increases iff `depths[i + window] > depths[i]`.
+    pub fn push(&mut self, depth: i64) -> Option<WindowStep> {
+        self.depths.push_back(depth);
+        self.sum += depth;
+        let dropped = match self.depths.len() > self.report.window {
+            true => self.depths.pop_front(),
+            false => None,
+        };
+        if let Some(dropped) = dropped {
+            self.sum -= dropped;
+        }
+        if self.depths.len() < self.report.window {
+            return None;
+        }
+
+        let change = dropped.map(|dropped| depth - dropped);
+        match change {
+            Some(change) if change > 0 => {
+                self.report.num_increasing += 1;
+                self.report.largest_increase = self.report.largest_increase.max(Some(change));
+                self.run += 1;
+            }
+            _ => self.run = 0,
+        }
+        self.report.longest_increasing_run = self.report.longest_increasing_run.max(self.run);
+
+        let step = WindowStep {
+            start: self.num_windows,
+            sum: self.sum,
+            change,
+        };
+        self.num_windows += 1;
+        return Some(step);
+    }
+
+    /// Finishes the analysis, without a trace
+    pub fn finish(self) -> SonarReport {
+        self.report
+    }
+}
+
+/// Analyzes the depth series with a sliding window of the given size
+pub fn analyze(depths: &[i64], window: usize) -> Result<SonarReport, AocError> {
+    let mut tracker = WindowTracker::new(window)?;
+    let mut trace = Vec::new();
+    for &depth in depths.iter() {
+        trace.extend(tracker.push(depth));
+    }
+    return Ok(SonarReport {
+        trace,
+        ..tracker.finish()
+    });
+}
+
+/// Prints the depths of a window of the sweep along with the change of its sum
+fn trace_step<D: fmt::Debug + ?Sized>(depths: &D, step: &WindowStep, ctx: &Context) {
+    let change = match step.change {
+        Some(change) => format!("{:+}", change),
+        None => String::from("n/a"),
+    };
+    debug!(ctx, "{:?}  ->  {}  ({})", depths, step.sum, change);
+}
+
+/// Prints the statistics of the analysis and returns the answer, i.e. the
+/// number of increasing windows
+fn conclude(report: &SonarReport, ctx: &Context) -> Answer {
+    verbose!(
+        ctx,
+        "\nWindow size:  {}\nLongest increasing run:  {}\nLargest increase:  {}",
+        report.window,
+        report.longest_increasing_run,
+        report
+            .largest_increase
+            .map_or(String::from("n/a"), |change| change.to_string())
+    );
+    let num_increasing = report.num_increasing as i64;
+    return num_increasing.into();
+}
+
+/// Counts the increasing windows of the depth series
+fn solve(depths: &Vec<i64>, window: usize, ctx: &Context) -> Result<Answer, AocError> {
+    let report = analyze(depths, window)?;
+    for step in report.trace.iter() {
+        trace_step(&depths[step.start..step.start + window], step, ctx);
+    }
+    return Ok(conclude(&report, ctx));
+}
+
+/// Counts the increasing windows of the depth series read line by line, in
+/// memory proportional to the window size
+fn solve_stream(
+    reader: &mut dyn BufRead,
+    window: usize,
+    ctx: &Context,
+) -> Result<Answer, AocError> {
+    let mut tracker = WindowTracker::new(window)?;
+    for line in stream_lines(reader) {
+        let (line_no, line) = line?;
+        if let Some(step) = tracker.push(parse_depth((line_no, &line))?) {
+            trace_step(tracker.depths(), &step, ctx);
+        }
+    }
+    return Ok(conclude(&tracker.finish(), ctx));
+}
 
 /// Implements the solution for part 1
 ///
 /// Simply counts the number of times the depth increases in the input data
-pub fn solve_part1(input: &String, _input_mode: &InputMode) -> i64 {
-    let mut previous_depth = -1;
-    let mut num_increasing = -1; // starting at -1 to account for first value
-
-    // Iterate over lines, mapped to integer values
-    // NOTE Avoiding to collect into a vector to allow lazy evaluation
-    for depth in input.lines().map(|x| x.parse::<i64>().unwrap()) {
-        if previous_depth < depth {
-            num_increasing += 1;
-        }
-        previous_depth = depth;
-    }
-    return num_increasing;
+pub fn solve_part1(depths: &Vec<i64>, ctx: &Context) -> Result<Answer, AocError> {
+    return solve(depths, ctx.window.unwrap_or(PART1_WINDOW), ctx);
 }
 
 /// Implements the solution for part 2
 ///
 /// Same as part 1 but with a three-measurement sliding window
-pub fn solve_part2(input: &String, input_mode: &InputMode) -> i64 {
-    let mut sum_of_depths;
-    let mut previous_sum_of_depths = -1;
-    let mut num_increasing = -1; // starting at -1 to account for first value
+pub fn solve_part2(depths: &Vec<i64>, ctx: &Context) -> Result<Answer, AocError> {
+    return solve(depths, ctx.window.unwrap_or(PART2_WINDOW), ctx);
+}
 
-    // Prepare iterators in a way that they are shifted relatively to each other
-    let map_to_int = |x: &str| x.parse::<i64>().unwrap();
+/// Implements the solution for part 1 on the input read line by line
+pub fn stream_part1(reader: &mut dyn BufRead, ctx: &Context) -> Result<Answer, AocError> {
+    return solve_stream(reader, ctx.window.unwrap_or(PART1_WINDOW), ctx);
+}
 
-    let iter1 = input.lines().map(map_to_int);
+/// Implements the solution for part 2 on the input read line by line
+pub fn stream_part2(reader: &mut dyn BufRead, ctx: &Context) -> Result<Answer, AocError> {
+    return solve_stream(reader, ctx.window.unwrap_or(PART2_WINDOW), ctx);
+}
 
-    let mut iter2 = input.lines().map(map_to_int);
-    iter2.next();
+// -----------------------------------------------------------------------------
 
-    let mut iter3 = input.lines().map(map_to_int);
-    iter3.next();
-    iter3.next();
+/// The solution for day 01
+pub struct Day01;
 
-    // Iterate over zipped iterators, creating a three-measurement window
-    for ((d1, d2), d3) in iter1.zip(iter2).zip(iter3) {
-        sum_of_depths = d1 + d2 + d3;
+impl Solution for Day01 {
+    const DAY: u8 = DAY;
+    type Input = Vec<i64>;
 
-        if matches!(input_mode, InputMode::Test) {
-            println!("{:?}  ->  {}", (d1, d2, d3), sum_of_depths);
-        }
+    fn parse(input: &String) -> Result<Vec<i64>, AocError> {
+        read_depths(input)
+    }
 
-        if previous_sum_of_depths < sum_of_depths {
-            num_increasing += 1;
-        }
-        previous_sum_of_depths = sum_of_depths;
+    fn part1(input: &Vec<i64>, ctx: &Context) -> Result<Answer, AocError> {
+        solve_part1(input, ctx)
     }
-    return num_increasing;
+
+    fn part2(input: &Vec<i64>, ctx: &Context) -> Result<Answer, AocError> {
+        solve_part2(input, ctx)
+    }
+
+    const STREAMING: Option<[StreamFunc; 2]> = Some([stream_part1, stream_part2]);
 }
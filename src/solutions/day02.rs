@@ -2,58 +2,370 @@
 //!
 //! For puzzle text, see https://adventofcode.com/2021/day/2
 
-use crate::InputMode;
+use std::fmt;
+use std::io::BufRead;
 
-/// Implements the solution for part 1
-pub fn solve_part1(input: &String, _input_mode: &InputMode) -> i64 {
-    let mut pos = [0, 0]; // x, y
-    let to_int = |v: &str| v.replace(" ", "").parse::<i64>().unwrap();
-
-    for line in input.lines() {
-        match line.split_at(line.find(" ").unwrap()) {
-            ("forward", x) => pos[0] += to_int(x),
-            ("up", y) => pos[1] -= to_int(y),
-            ("down", y) => pos[1] += to_int(y),
-            _ => panic!("Invalid input line: {:?}", line),
+use crate::solutions::stream_lines;
+use crate::{debug, verbose, Answer, AocError, Context, Solution, StreamFunc};
+
+const DAY: u8 = 2;
+
+/// A command of the planned course, along with its argument
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Command {
+    Forward(i64),
+    Down(i64),
+    Up(i64),
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Command::Forward(value) => write!(f, "forward {}", value),
+            Command::Down(value) => write!(f, "down {}", value),
+            Command::Up(value) => write!(f, "up {}", value),
         }
+    }
+}
+
+/// Parses a line like `forward 5` into a command; expects a 1-based line number
+pub fn parse_command(line_no: usize, line: &str) -> Result<Command, AocError> {
+    let parse_error = |reason: String| AocError::parse(DAY, line_no, line, reason);
 
-        println!("Applied instruction:  {}\t  Now at: {:?}", line, pos);
+    let (name, value) = match line.split_whitespace().collect::<Vec<_>>().as_slice() {
+        [name, value] => (*name, *value),
+        _ => return Err(parse_error(String::from("expected `<command> <value>`"))),
+    };
+    let value = value
+        .parse::<i64>()
+        .map_err(|err| parse_error(err.to_string()))?;
+    match name {
+        "forward" => Ok(Command::Forward(value)),
+        "down" => Ok(Command::Down(value)),
+        "up" => Ok(Command::Up(value)),
+        _ => Err(parse_error(format!("unknown command {:?}", name))),
     }
+}
 
-    println!("\nFinal position:  {:?}", pos);
-    return pos[0] * pos[1];
+/// Parses all lines of the input into commands
+pub fn read_commands(input: &String) -> Result<Vec<Command>, AocError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(n, line)| parse_command(n + 1, line))
+        .collect()
 }
 
-/// Implements the solution for part 2
-pub fn solve_part2(input: &String, _input_mode: &InputMode) -> i64 {
-    #[derive(Debug)]
-    struct State {
-        x: i64,
-        y: i64,
-        aim: i64,
-    }
-
-    let mut state = State { x: 0, y: 0, aim: 0 };
-    let to_int = |v: &str| v.replace(" ", "").parse::<i64>().unwrap();
-
-    for line in input.lines() {
-        match line.split_at(line.find(" ").unwrap()) {
-            ("forward", delta_x) => {
-                state.x += to_int(delta_x);
-                state.y += to_int(delta_x) * state.aim;
-            }
-            ("up", delta_aim) => {
-                state.aim -= to_int(delta_aim);
-            }
-            ("down", delta_aim) => {
-                state.aim += to_int(delta_aim);
+/// Parses the lines of a streamed input into commands, one at a time
+pub fn stream_commands(
+    reader: &mut dyn BufRead,
+) -> impl Iterator<Item = Result<Command, AocError>> + '_ {
+    stream_lines(reader).map(|line| {
+        let (line_no, line) = line?;
+        parse_command(line_no + 1, &line)
+    })
+}
+
+// -----------------------------------------------------------------------------
+
+/// A position of the submarine; the depth increases downwards, i.e. negative
+/// depths are above the surface
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Position {
+    pub horizontal: i64,
+    pub depth: i64,
+}
+
+/// The semantics of the commands, i.e. how a submarine moves when following
+/// them
+pub trait Submarine: fmt::Debug {
+    /// Follows a single command
+    fn apply(&mut self, command: &Command);
+
+    /// The current position of the submarine
+    fn position(&self) -> Position;
+
+    /// The current aim of the submarine, if the model has one
+    fn aim(&self) -> Option<i64> {
+        None
+    }
+}
+
+/// A submarine which is moved directly by all commands (part 1)
+#[derive(Debug, Default)]
+pub struct SimpleSubmarine {
+    pos: Position,
+}
+
+impl Submarine for SimpleSubmarine {
+    fn apply(&mut self, command: &Command) {
+        match *command {
+            Command::Forward(x) => self.pos.horizontal += x,
+            Command::Down(y) => self.pos.depth += y,
+            Command::Up(y) => self.pos.depth -= y,
+        }
+    }
+
+    fn position(&self) -> Position {
+        self.pos
+    }
+}
+
+/// A submarine which is aimed by `down` and `up`, and moves along its aim
+/// when going forward (part 2)
+#[derive(Debug, Default)]
+pub struct AimingSubmarine {
+    pos: Position,
+    aim: i64,
+}
+
+impl Submarine for AimingSubmarine {
+    fn apply(&mut self, command: &Command) {
+        match *command {
+            Command::Forward(delta_x) => {
+                self.pos.horizontal += delta_x;
+                self.pos.depth += delta_x * self.aim;
             }
-            _ => panic!("Invalid input line: {:?}", line),
+            Command::Down(delta_aim) => self.aim += delta_aim,
+            Command::Up(delta_aim) => self.aim -= delta_aim,
+        }
+    }
+
+    fn position(&self) -> Position {
+        self.pos
+    }
+
+    fn aim(&self) -> Option<i64> {
+        Some(self.aim)
+    }
+}
+
+/// A point of a trajectory, i.e. the state of the submarine after a step of
+/// the course; step 0 is the initial state, without a command
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Waypoint {
+    pub step: usize,
+    pub command: Option<Command>,
+    pub position: Position,
+    pub aim: Option<i64>,
+}
+
+/// Replays a course on a submarine, keeping track of how deep it went and
+/// whether it surfaced
+#[derive(Debug)]
+pub struct Interpreter<S: Submarine> {
+    pub submarine: S,
+    /// The largest depth reached so far
+    pub max_depth: i64,
+    /// Whether the submarine has been above the surface, i.e. at a negative
+    /// depth, so far
+    pub surfaced: bool,
+}
+
+impl<S: Submarine> Interpreter<S> {
+    pub fn new(submarine: S) -> Interpreter<S> {
+        let depth = submarine.position().depth;
+        Interpreter {
+            submarine,
+            max_depth: depth,
+            surfaced: depth < 0,
         }
+    }
+
+    /// Follows a single command, returning the new position
+    pub fn step(&mut self, command: &Command) -> Position {
+        self.submarine.apply(command);
+        let pos = self.submarine.position();
+        self.max_depth = self.max_depth.max(pos.depth);
+        self.surfaced |= pos.depth < 0;
+        return pos;
+    }
+
+    /// The current state of the submarine as a point of its trajectory
+    fn waypoint(&self, step: usize, command: Option<Command>) -> Waypoint {
+        Waypoint {
+            step,
+            command,
+            position: self.submarine.position(),
+            aim: self.submarine.aim(),
+        }
+    }
+
+    /// Follows all commands of a course, returning the trajectory, i.e. the
+    /// initial state followed by the state after each command
+    pub fn replay(&mut self, commands: &[Command]) -> Vec<Waypoint> {
+        let mut trajectory = vec![self.waypoint(0, None)];
+        for (step, command) in (1..).zip(commands.iter()) {
+            self.step(command);
+            trajectory.push(self.waypoint(step, Some(*command)));
+        }
+        return trajectory;
+    }
+}
+
+/// Replays a course with the submarine model of the given part
+pub fn trajectory(commands: &[Command], part: u8) -> Vec<Waypoint> {
+    match part {
+        1 => Interpreter::new(SimpleSubmarine::default()).replay(commands),
+        _ => Interpreter::new(AimingSubmarine::default()).replay(commands),
+    }
+}
+
+/// Formats a trajectory as CSV with the columns step, command, x, depth, and
+/// aim; the aim is left empty for models without one
+pub fn trajectory_csv(trajectory: &[Waypoint]) -> String {
+    let mut csv = String::from("step,command,x,depth,aim\n");
+    for waypoint in trajectory.iter() {
+        csv += &format!(
+            "{},{},{},{},{}\n",
+            waypoint.step,
+            waypoint.command.map_or(String::new(), |c| c.to_string()),
+            waypoint.position.horizontal,
+            waypoint.position.depth,
+            waypoint.aim.map_or(String::new(), |aim| aim.to_string())
+        );
+    }
+    return csv;
+}
+
+/// The size of the plot area of the SVG depth profile, in pixels
+const SVG_SIZE: (f64, f64) = (800., 400.);
+
+/// The margin around the plot area of the SVG depth profile, in pixels
+const SVG_MARGIN: f64 = 40.;
+
+/// Renders a trajectory as an SVG depth profile, i.e. the depth over the
+/// horizontal position, with the surface as a dashed line
+pub fn trajectory_svg(trajectory: &[Waypoint]) -> String {
+    let (width, height) = SVG_SIZE;
+    let xs = trajectory.iter().map(|w| w.position.horizontal);
+    let depths = trajectory.iter().map(|w| w.position.depth);
+    let (min_x, max_x) = (xs.clone().min().unwrap_or(0), xs.max().unwrap_or(0));
+    let min_depth = depths.clone().min().unwrap_or(0).min(0);
+    let max_depth = depths.max().unwrap_or(0).max(0);
+
+    // Map positions into the plot area, with depths increasing downwards
+    let scale_x = width / (max_x - min_x).max(1) as f64;
+    let scale_y = height / (max_depth - min_depth).max(1) as f64;
+    let to_svg = |x: i64, depth: i64| {
+        (
+            SVG_MARGIN + (x - min_x) as f64 * scale_x,
+            SVG_MARGIN + (depth - min_depth) as f64 * scale_y,
+        )
+    };
+
+    let points = trajectory
+        .iter()
+        .map(|w| {
+            let (x, y) = to_svg(w.position.horizontal, w.position.depth);
+            format!("{:.2},{:.2}", x, y)
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    let (_, surface_y) = to_svg(min_x, 0);
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" \
+        viewBox=\"0 0 {w} {h}\">\n",
+        w = width + 2. * SVG_MARGIN,
+        h = height + 2. * SVG_MARGIN
+    );
+    svg += "  <rect width=\"100%\" height=\"100%\" fill=\"white\"/>\n";
+    svg += &format!(
+        "  <line x1=\"{x1}\" y1=\"{y:.2}\" x2=\"{x2}\" y2=\"{y:.2}\" \
+        stroke=\"steelblue\" stroke-dasharray=\"4 4\"/>\n",
+        x1 = SVG_MARGIN,
+        x2 = SVG_MARGIN + width,
+        y = surface_y
+    );
+    svg += &format!(
+        "  <polyline points=\"{}\" fill=\"none\" stroke=\"black\" stroke-width=\"1.5\"/>\n",
+        points
+    );
+    svg += &format!(
+        "  <text x=\"{}\" y=\"{}\" font-family=\"sans-serif\" font-size=\"12\">\
+        x: {} .. {}, depth: {} .. {}, steps: {}</text>\n",
+        SVG_MARGIN,
+        SVG_MARGIN / 2.,
+        min_x,
+        max_x,
+        min_depth,
+        max_depth,
+        trajectory.len().saturating_sub(1)
+    );
+    svg += "</svg>\n";
+    return svg;
+}
+
+/// Navigates a submarine along the course and returns the product of the
+/// final horizontal position and depth
+fn navigate<S: Submarine>(
+    submarine: S,
+    commands: impl Iterator<Item = Result<Command, AocError>>,
+    ctx: &Context,
+) -> Result<Answer, AocError> {
+    let mut interpreter = Interpreter::new(submarine);
+    for command in commands {
+        let command = command?;
+        interpreter.step(&command);
+        debug!(
+            ctx,
+            "Applied command:  {}\t  ==>  {:?}", command, interpreter.submarine
+        );
+    }
+
+    let pos = interpreter.submarine.position();
+    verbose!(
+        ctx,
+        "\nFinal position:  ({}, {})\nMaximum depth:  {}\nSurfaced:  {}",
+        pos.horizontal,
+        pos.depth,
+        interpreter.max_depth,
+        interpreter.surfaced
+    );
+    return Ok((pos.horizontal * pos.depth).into());
+}
+
+/// Implements the solution for part 1
+pub fn solve_part1(commands: &Vec<Command>, ctx: &Context) -> Result<Answer, AocError> {
+    return navigate(SimpleSubmarine::default(), commands.iter().copied().map(Ok), ctx);
+}
+
+/// Implements the solution for part 2
+pub fn solve_part2(commands: &Vec<Command>, ctx: &Context) -> Result<Answer, AocError> {
+    return navigate(AimingSubmarine::default(), commands.iter().copied().map(Ok), ctx);
+}
+
+/// Implements the solution for part 1 on the input read line by line
+pub fn stream_part1(reader: &mut dyn BufRead, ctx: &Context) -> Result<Answer, AocError> {
+    return navigate(SimpleSubmarine::default(), stream_commands(reader), ctx);
+}
+
+/// Implements the solution for part 2 on the input read line by line
+pub fn stream_part2(reader: &mut dyn BufRead, ctx: &Context) -> Result<Answer, AocError> {
+    return navigate(AimingSubmarine::default(), stream_commands(reader), ctx);
+}
+
+// -----------------------------------------------------------------------------
+
+/// The solution for day 02
+pub struct Day02;
+
+impl Solution for Day02 {
+    const DAY: u8 = DAY;
+    type Input = Vec<Command>;
+
+    fn parse(input: &String) -> Result<Vec<Command>, AocError> {
+        read_commands(input)
+    }
+
+    fn part1(input: &Vec<Command>, ctx: &Context) -> Result<Answer, AocError> {
+        solve_part1(input, ctx)
+    }
 
-        println!("Applied instruction:  {}\t  ==>  {:?}", line, state);
+    fn part2(input: &Vec<Command>, ctx: &Context) -> Result<Answer, AocError> {
+        solve_part2(input, ctx)
     }
 
-    println!("\nFinal position:  ({}, {})", state.x, state.y);
-    return state.x * state.y;
+    const STREAMING: Option<[StreamFunc; 2]> = Some([stream_part1, stream_part2]);
 }
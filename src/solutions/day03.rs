@@ -2,27 +2,30 @@
 //!
 //! For puzzle text, see https://adventofcode.com/2021/day/3
 
-use crate::InputMode;
+use crate::{debug, verbose, Answer, AocError, Context, Solution, Variant};
 
+const DAY: u8 = 3;
+
+/// A binary number, most significant bit first
 pub type BinVec = Vec<bool>;
 
 /// Converts a string of 0 and 1 to a BinVec
-fn str2binvec(s: &str) -> BinVec {
+pub fn str2binvec(s: &str) -> Result<BinVec, String> {
     let mut binvec = BinVec::new();
 
     for c in s.chars() {
         match c {
             '0' => binvec.push(false),
             '1' => binvec.push(true),
-            _ => panic!("Invalid character: {:?}", c),
+            _ => return Err(format!("invalid character {:?}", c)),
         }
     }
 
-    return binvec;
+    return Ok(binvec);
 }
 
 /// Converts a binary value (encoded as BinVec) to a decimal value
-fn bin2dec(vec: &BinVec) -> i64 {
+pub fn bin2dec(vec: &BinVec) -> i64 {
     let mut dec: i64 = 0;
     for (n, bit) in vec.iter().rev().enumerate() {
         match bit {
@@ -33,19 +36,42 @@ fn bin2dec(vec: &BinVec) -> i64 {
     return dec;
 }
 
+/// The maximum width of binary numbers converted via [`bin2dec`]
+pub const MAX_BINVEC_WIDTH: usize = i64::BITS as usize - 1;
+
 /// Reads multi-line string data into a vector of binary representations
-fn read_into_binvec(input: &String) -> Vec<BinVec> {
-    return input
-        .lines()
-        .map(|line| str2binvec(&line))
-        .collect::<Vec<BinVec>>();
+///
+/// All binary numbers are required to have the same, non-zero width of at
+/// most [`MAX_BINVEC_WIDTH`].
+pub fn read_into_binvec(input: &String) -> Result<Vec<BinVec>, AocError> {
+    let mut data = Vec::<BinVec>::new();
+
+    for (n, line) in input.lines().enumerate() {
+        let binary_num =
+            str2binvec(line).map_err(|reason| AocError::parse(DAY, n + 1, line, reason))?;
+        if binary_num.is_empty()
+            || binary_num.len() > MAX_BINVEC_WIDTH
+            || (n > 0 && binary_num.len() != data[0].len())
+        {
+            let reason = format!("unexpected width {}", binary_num.len());
+            return Err(AocError::parse(DAY, n + 1, line, reason));
+        }
+        data.push(binary_num);
+    }
+
+    if data.is_empty() {
+        return Err(AocError::InvalidInput(String::from(
+            "no binary numbers given",
+        )));
+    }
+    return Ok(data);
 }
 
 /// Counts the number of zero and one bits in a certain bit position
-fn count_bits(data: &Vec<BinVec>, bit_pos: usize) -> (u32, u32) {
+pub fn count_bits(data: &Vec<BinVec>, bit_pos: usize) -> (u32, u32) {
     let mut n0 = 0;
     for binary_num in data.iter() {
-        if binary_num[bit_pos] == false {
+        if !binary_num[bit_pos] {
             n0 += 1;
         }
     }
@@ -58,17 +84,32 @@ fn count_bits(data: &Vec<BinVec>, bit_pos: usize) -> (u32, u32) {
 /// are to be *kept*. More precisely: The numbers that have that bit in the
 /// currently chosen bit position are kept and the others are discarded.
 /// The binary predicate is called with the number of 0 and 1 bits.
-fn filter_by_bit_pattern(data: &Vec<BinVec>, predicate: fn(u32, u32) -> bool) -> BinVec {
+pub fn filter_by_bit_pattern(
+    data: &Vec<BinVec>,
+    predicate: fn(u32, u32) -> bool,
+    ctx: &Context,
+) -> Result<BinVec, AocError> {
     let mut bit_pos = 0;
     let mut filtered = data.clone();
 
-    println!("Filtering {} binary numbers by bit pattern ...", data.len());
+    verbose!(
+        ctx,
+        "Filtering {} binary numbers by bit pattern ...",
+        data.len()
+    );
     while filtered.len() > 1 {
+        if bit_pos >= filtered[0].len() {
+            return Err(AocError::NoSolution(format!(
+                "{} identical binary numbers remain after filtering",
+                filtered.len()
+            )));
+        }
         let (n0, n1) = count_bits(&filtered, bit_pos);
         let keep_bit = predicate(n0, n1);
         filtered.retain(|bin: &BinVec| bin[bit_pos] == keep_bit);
 
-        println!(
+        debug!(
+            ctx,
             "  Bit position {:2}:  {} entries left.",
             bit_pos,
             filtered.len()
@@ -76,16 +117,26 @@ fn filter_by_bit_pattern(data: &Vec<BinVec>, predicate: fn(u32, u32) -> bool) ->
         bit_pos += 1;
     }
 
-    return filtered[0].to_vec();
+    match filtered.first() {
+        Some(binary_num) => Ok(binary_num.to_vec()),
+        None => Err(AocError::NoSolution(format!(
+            "no binary numbers remain after filtering bit position {}",
+            bit_pos - 1
+        ))),
+    }
 }
 
 // -----------------------------------------------------------------------------
 
-/// Implements the solution for part 1
-pub fn solve_part1(input: &String, _input_mode: &InputMode) -> i64 {
-    let data = read_into_binvec(input);
-    let num_cols = data.get(0).unwrap().len();
-    println!("Got {} binary numbers of width {}.", data.len(), num_cols);
+/// Implements the solution for part 1 on unpacked binary numbers
+pub fn solve_part1_binvec(data: &Vec<BinVec>, ctx: &Context) -> Result<Answer, AocError> {
+    let num_cols = data[0].len();
+    verbose!(
+        ctx,
+        "Got {} binary numbers of width {}.",
+        data.len(),
+        num_cols
+    );
 
     // Go over columns and count most frequent bit --> gamma rate
     let mut gamma_bin = BinVec::new();
@@ -94,9 +145,8 @@ pub fn solve_part1(input: &String, _input_mode: &InputMode) -> i64 {
     for bit_pos in 0..num_cols {
         num_true_bits = 0;
         for binary_num in data.iter() {
-            match binary_num[bit_pos] {
-                true => num_true_bits += 1,
-                false => {}
+            if binary_num[bit_pos] {
+                num_true_bits += 1;
             }
         }
         gamma_bin.push(num_true_bits > data.len() / 2);
@@ -111,25 +161,349 @@ pub fn solve_part1(input: &String, _input_mode: &InputMode) -> i64 {
     // Turn into decimal
     let gamma_dec = bin2dec(&gamma_bin);
     let epsilon_dec = bin2dec(&epsilon_bin);
-    println!("Gamma:   {:?}  =  {}", gamma_bin, gamma_dec);
-    println!("Epsilon: {:?}  =  {}", epsilon_bin, epsilon_dec);
-    return gamma_dec * epsilon_dec;
+    verbose!(ctx, "Gamma:   {:?}  =  {}", gamma_bin, gamma_dec);
+    verbose!(ctx, "Epsilon: {:?}  =  {}", epsilon_bin, epsilon_dec);
+    return Ok(Answer::from_unsigned(gamma_dec as u128 * epsilon_dec as u128));
 }
 
-/// Implements the solution for part 2
-pub fn solve_part2(input: &String, _input_mode: &InputMode) -> i64 {
-    let data = read_into_binvec(input);
-    let num_cols = data.get(0).unwrap().len();
-    println!("Got {} binary numbers of width {}.", data.len(), num_cols);
+/// Implements the solution for part 2 on unpacked binary numbers
+pub fn solve_part2_binvec(data: &Vec<BinVec>, ctx: &Context) -> Result<Answer, AocError> {
+    let num_cols = data[0].len();
+    verbose!(
+        ctx,
+        "Got {} binary numbers of width {}.",
+        data.len(),
+        num_cols
+    );
 
     // Iterate over columns and filter by numbers with the most common bit in
     // the respective position
-    let oxy_bin = filter_by_bit_pattern(&data, |n0: u32, n1: u32| n1 >= n0);
-    let co2_bin = filter_by_bit_pattern(&data, |n0: u32, n1: u32| n0 > n1);
+    let oxy_bin = filter_by_bit_pattern(data, |n0: u32, n1: u32| n1 >= n0, ctx)?;
+    let co2_bin = filter_by_bit_pattern(data, |n0: u32, n1: u32| n0 > n1, ctx)?;
 
     let oxy_dec = bin2dec(&oxy_bin);
     let co2_dec = bin2dec(&co2_bin);
-    println!("oxy:   {:?}  =  {}", oxy_bin, oxy_dec);
-    println!("co2:   {:?}  =  {}", co2_bin, co2_dec);
-    return oxy_dec * co2_dec;
+    verbose!(ctx, "oxy:   {:?}  =  {}", oxy_bin, oxy_dec);
+    verbose!(ctx, "co2:   {:?}  =  {}", co2_bin, co2_dec);
+    return Ok(Answer::from_unsigned(oxy_dec as u128 * co2_dec as u128));
+}
+
+// -----------------------------------------------------------------------------
+
+/// The maximum width of the binary numbers in a [`Diagnostic`]
+pub const MAX_WIDTH: usize = u64::BITS as usize;
+
+/// Binary numbers of a common width, packed into integers
+///
+/// Bit positions are counted from the least significant bit, i.e. the
+/// leftmost column of the input is bit `width - 1`. The numbers are kept in
+/// ascending order, which the column counts do not depend on.
+#[derive(Clone, Debug)]
+pub struct Diagnostic {
+    pub width: usize,
+    values: Vec<u64>,
+}
+
+impl Diagnostic {
+    /// Parses multi-line string data into packed binary numbers
+    ///
+    /// All binary numbers are required to have the same width, which must be
+    /// between 1 and [`MAX_WIDTH`].
+    pub fn parse(input: &String) -> Result<Diagnostic, AocError> {
+        let mut width = 0;
+        let mut values = Vec::new();
+
+        for (n, line) in input.lines().enumerate() {
+            let parse_error = |reason: String| AocError::parse(DAY, n + 1, line, reason);
+            if let Some(c) = line.chars().find(|c| *c != '0' && *c != '1') {
+                return Err(parse_error(format!("invalid character {:?}", c)));
+            }
+            if line.is_empty() || line.len() > MAX_WIDTH || (n > 0 && line.len() != width) {
+                return Err(parse_error(format!("unexpected width {}", line.len())));
+            }
+            width = line.len();
+            values.push(u64::from_str_radix(line, 2).map_err(|err| parse_error(err.to_string()))?);
+        }
+
+        if values.is_empty() {
+            return Err(AocError::InvalidInput(String::from(
+                "no binary numbers given",
+            )));
+        }
+        values.sort_unstable();
+        return Ok(Diagnostic { width, values });
+    }
+
+    /// The binary numbers, in ascending order
+    pub fn values(&self) -> &[u64] {
+        &self.values
+    }
+
+    /// The mask of all bits within the width
+    pub fn mask(&self) -> u64 {
+        u64::MAX >> (MAX_WIDTH - self.width)
+    }
+
+    /// Counts the one bits of every column, indexed by bit position
+    ///
+    /// Uses bit-sliced counters, i.e. bit `i` of plane `k` holds bit `k` of
+    /// the count of column `i`; adding a number ripples its bits through the
+    /// planes as carries, such that all columns are counted in parallel.
+    pub fn column_popcounts(&self) -> Vec<usize> {
+        let mut planes: Vec<u64> = Vec::new();
+        for &value in self.values.iter() {
+            let mut carry = value;
+            for plane in planes.iter_mut() {
+                if carry == 0 {
+                    break;
+                }
+                (*plane, carry) = (*plane ^ carry, *plane & carry);
+            }
+            if carry != 0 {
+                planes.push(carry);
+            }
+        }
+
+        return (0..self.width)
+            .map(|bit| {
+                (0..planes.len())
+                    .map(|k| (((planes[k] >> bit) & 1) as usize) << k)
+                    .sum()
+            })
+            .collect();
+    }
+
+    /// The mask of the bits which are most common in their column; ties count
+    /// as zeros
+    pub fn most_common_mask(&self) -> u64 {
+        let num_values = self.values.len();
+        return self
+            .column_popcounts()
+            .iter()
+            .enumerate()
+            .filter(|(_, &ones)| 2 * ones > num_values)
+            .fold(0, |mask, (bit, _)| mask | (1 << bit));
+    }
+
+    /// The mask of the bits which are least common in their column; ties
+    /// count as ones
+    pub fn least_common_mask(&self) -> u64 {
+        !self.most_common_mask() & self.mask()
+    }
+
+    /// Selects a single number by narrowing down the numbers column by column,
+    /// from the most significant bit on
+    ///
+    /// In each column, `keep_ones` decides on the number of zero and one bits
+    /// among the remaining numbers whether to keep those with a one or a zero
+    /// bit. As the numbers are sorted, the remaining ones always share their
+    /// more significant bits and thus form a range of indices, which is split
+    /// by a binary search at the first number with a one bit.
+    pub fn select(
+        &self,
+        keep_ones: fn(usize, usize) -> bool,
+        ctx: &Context,
+    ) -> Result<u64, AocError> {
+        let sorted = &self.values;
+        let (mut start, mut end) = (0, sorted.len());
+        let mut bit_pos = 0;
+
+        for bit in (0..self.width).rev() {
+            if end - start <= 1 {
+                break;
+            }
+            bit_pos = self.width - 1 - bit;
+            let split = start + sorted[start..end].partition_point(|value| (value >> bit) & 1 == 0);
+            match keep_ones(split - start, end - split) {
+                true => start = split,
+                false => end = split,
+            }
+            debug!(
+                ctx,
+                "  Bit position {:2}:  {} entries left.",
+                bit_pos,
+                end - start
+            );
+        }
+
+        match end - start {
+            0 => Err(AocError::NoSolution(format!(
+                "no binary numbers remain after filtering bit position {}",
+                bit_pos
+            ))),
+            1 => Ok(sorted[start]),
+            num_left => Err(AocError::NoSolution(format!(
+                "{} identical binary numbers remain after filtering",
+                num_left
+            ))),
+        }
+    }
+
+    /// Formats a number as binary digits of the width
+    pub fn format(&self, value: u64) -> String {
+        format!("{:0width$b}", value, width = self.width)
+    }
+}
+
+/// Multiplies two numbers of up to 64 bits, which may exceed an integer answer
+fn product(a: u64, b: u64) -> Answer {
+    Answer::from_unsigned(a as u128 * b as u128)
+}
+
+/// Implements the solution for part 1
+pub fn solve_part1(diagnostic: &Diagnostic, ctx: &Context) -> Result<Answer, AocError> {
+    verbose!(
+        ctx,
+        "Got {} binary numbers of width {}.",
+        diagnostic.values().len(),
+        diagnostic.width
+    );
+
+    let gamma = diagnostic.most_common_mask();
+    let epsilon = diagnostic.least_common_mask();
+    verbose!(ctx, "Gamma:   {}  =  {}", diagnostic.format(gamma), gamma);
+    verbose!(ctx, "Epsilon: {}  =  {}", diagnostic.format(epsilon), epsilon);
+    return Ok(product(gamma, epsilon));
+}
+
+/// Implements the solution for part 2
+pub fn solve_part2(diagnostic: &Diagnostic, ctx: &Context) -> Result<Answer, AocError> {
+    verbose!(
+        ctx,
+        "Got {} binary numbers of width {}.",
+        diagnostic.values().len(),
+        diagnostic.width
+    );
+
+    // Narrow down the numbers to those with the most and least common bits
+    let oxy = diagnostic.select(|n0, n1| n1 >= n0, ctx)?;
+    let co2 = diagnostic.select(|n0, n1| n1 < n0, ctx)?;
+    verbose!(ctx, "oxy:   {}  =  {}", diagnostic.format(oxy), oxy);
+    verbose!(ctx, "co2:   {}  =  {}", diagnostic.format(co2), co2);
+    return Ok(product(oxy, co2));
+}
+
+// -----------------------------------------------------------------------------
+
+/// The solution for day 03
+pub struct Day03;
+
+impl Solution for Day03 {
+    const DAY: u8 = DAY;
+    type Input = Diagnostic;
+
+    fn parse(input: &String) -> Result<Diagnostic, AocError> {
+        Diagnostic::parse(input)
+    }
+
+    fn part1(input: &Diagnostic, ctx: &Context) -> Result<Answer, AocError> {
+        solve_part1(input, ctx)
+    }
+
+    fn part2(input: &Diagnostic, ctx: &Context) -> Result<Answer, AocError> {
+        solve_part2(input, ctx)
+    }
+
+    fn variants() -> Vec<Variant> {
+        vec![Variant::of::<Day03BinVec>("binvec")]
+    }
+}
+
+/// The previous solution for day 03 on unpacked binary numbers, kept for
+/// comparison
+pub struct Day03BinVec;
+
+impl Solution for Day03BinVec {
+    const DAY: u8 = DAY;
+    type Input = Vec<BinVec>;
+
+    fn parse(input: &String) -> Result<Vec<BinVec>, AocError> {
+        read_into_binvec(input)
+    }
+
+    fn part1(input: &Vec<BinVec>, ctx: &Context) -> Result<Answer, AocError> {
+        solve_part1_binvec(input, ctx)
+    }
+
+    fn part2(input: &Vec<BinVec>, ctx: &Context) -> Result<Answer, AocError> {
+        solve_part2_binvec(input, ctx)
+    }
+}
+
+// -----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{InputMode, Verbosity};
+
+    const EXAMPLE: &str = "00100\n11110\n10110\n10111\n10101\n01111\n\
+                           00111\n11100\n10000\n11001\n00010\n01010\n";
+
+    fn quiet() -> Context {
+        Context::new(InputMode::Test, Verbosity::Quiet)
+    }
+
+    fn parse_both(input: &str) -> (Diagnostic, Vec<BinVec>) {
+        let input = String::from(input);
+        (
+            Diagnostic::parse(&input).unwrap(),
+            read_into_binvec(&input).unwrap(),
+        )
+    }
+
+    #[test]
+    fn column_popcounts_match_count_bits() {
+        for input in [EXAMPLE, "0001\n0011\n0111\n0110\n", "1\n1\n1\n1\n1\n1\n1\n0\n"] {
+            let (diagnostic, binvecs) = parse_both(input);
+            let counts = diagnostic.column_popcounts();
+            for col in 0..diagnostic.width {
+                let (_, n1) = count_bits(&binvecs, col);
+                assert_eq!(counts[diagnostic.width - 1 - col], n1 as usize);
+            }
+        }
+    }
+
+    #[test]
+    fn tied_column_counts_as_zero() {
+        let input = "0001\n0011\n0111\n0110\n";
+        let (diagnostic, binvecs) = parse_both(input);
+        assert_eq!(diagnostic.most_common_mask(), 0b0011);
+        assert_eq!(diagnostic.least_common_mask(), 0b1100);
+        assert_eq!(
+            solve_part1(&diagnostic, &quiet()).unwrap().to_string(),
+            solve_part1_binvec(&binvecs, &quiet()).unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn select_matches_filter_by_bit_pattern() {
+        for input in [EXAMPLE, "101\n011\n110\n000\n", "1100\n1010\n0110\n0101\n0011\n1001\n"] {
+            let (diagnostic, binvecs) = parse_both(input);
+            let oxy = diagnostic.select(|n0, n1| n1 >= n0, &quiet()).unwrap();
+            let co2 = diagnostic.select(|n0, n1| n1 < n0, &quiet()).unwrap();
+            let oxy_binvec = filter_by_bit_pattern(&binvecs, |n0, n1| n1 >= n0, &quiet()).unwrap();
+            let co2_binvec = filter_by_bit_pattern(&binvecs, |n0, n1| n0 > n1, &quiet()).unwrap();
+            assert_eq!(oxy as i64, bin2dec(&oxy_binvec));
+            assert_eq!(co2 as i64, bin2dec(&co2_binvec));
+        }
+    }
+
+    #[test]
+    fn emptied_filter_is_no_solution() {
+        let (_, binvecs) = parse_both("00\n01\n");
+        let co2 = filter_by_bit_pattern(&binvecs, |n0, n1| n0 > n1, &quiet());
+        assert!(matches!(co2, Err(AocError::NoSolution(_))));
+
+        let (diagnostic, _) = parse_both("00\n01\n");
+        let co2 = diagnostic.select(|n0, n1| n1 < n0, &quiet());
+        assert!(matches!(co2, Err(AocError::NoSolution(_))));
+    }
+
+    #[test]
+    fn example_answers() {
+        let (diagnostic, _) = parse_both(EXAMPLE);
+        assert_eq!(solve_part1(&diagnostic, &quiet()).unwrap().to_string(), "198");
+        assert_eq!(solve_part2(&diagnostic, &quiet()).unwrap().to_string(), "230");
+    }
 }
@@ -8,36 +8,58 @@ use std::collections::HashMap;
 #[allow(unused_imports)]
 use ndarray::prelude::*;
 
-use crate::InputMode;
-
-type Board = ndarray::Array2<i64>;
-type Winners = HashMap<usize, i64>; // maps winning position to score
+use crate::{debug, verbose, Answer, AocError, Context, Solution};
+
+const DAY: u8 = 4;
+
+/// A bingo board, with extra row and column for the counters
+pub type Board = ndarray::Array2<i64>;
+pub type Winners = HashMap<usize, i64>; // maps winning position to score
+
+/// Parses a whitespace- or comma-separated list of non-negative numbers
+pub fn parse_numbers<'a>(
+    line_no: usize,
+    line: &str,
+    numbers: impl Iterator<Item = &'a str>,
+) -> Result<Vec<i64>, AocError> {
+    numbers
+        .map(|n| match n.parse::<i64>() {
+            Ok(n) if n >= 0 => Ok(n),
+            Ok(n) => Err(AocError::parse(
+                DAY,
+                line_no,
+                line,
+                format!("negative number {}", n),
+            )),
+            Err(err) => Err(AocError::parse(DAY, line_no, line, err)),
+        })
+        .collect()
+}
 
 /// Parses string input and returns the numbers to draw and the bingo boards
-fn read_numbers_and_boards(input: &String) -> (Vec<i64>, Vec<Board>) {
-    let mut lines_iter = input.lines();
-    let numbers = lines_iter
-        .next()
-        .unwrap()
-        .split(",")
-        .map(|n| n.parse::<i64>().unwrap())
-        .collect::<Vec<i64>>();
+pub fn read_numbers_and_boards(input: &String) -> Result<(Vec<i64>, Vec<Board>), AocError> {
+    let mut lines_iter = input.lines().enumerate();
+    let numbers = match lines_iter.next() {
+        Some((_, line)) => parse_numbers(1, line, line.split(","))?,
+        None => return Err(AocError::InvalidInput(String::from("input is empty"))),
+    };
 
     let mut boards = Vec::<Board>::new();
     let mut current_board = Board::zeros((6, 6));
     let mut row = 0;
-    for line in lines_iter {
-        if line.len() == 0 {
+    for (n, line) in lines_iter {
+        if line.is_empty() {
             // Nothing to do
             continue;
         }
 
         // Set the corresponding row in the current board
-        for (col, val) in line
-            .split_whitespace()
-            .map(|n| n.parse::<i64>().unwrap())
-            .enumerate()
-        {
+        let values = parse_numbers(n + 1, line, line.split_whitespace())?;
+        if values.len() != 5 {
+            let reason = format!("expected 5 numbers per board row, got {}", values.len());
+            return Err(AocError::parse(DAY, n + 1, line, reason));
+        }
+        for (col, val) in values.into_iter().enumerate() {
             current_board.row_mut(row)[col] = val;
         }
 
@@ -61,7 +83,12 @@ fn read_numbers_and_boards(input: &String) -> (Vec<i64>, Vec<Board>) {
         row = 0;
     }
 
-    return (numbers, boards);
+    if row != 0 {
+        return Err(AocError::InvalidInput(String::from(
+            "last board is incomplete",
+        )));
+    }
+    return Ok((numbers, boards));
 }
 
 /// Goes through the boards, marks matching numbers, and updates counters
@@ -69,8 +96,8 @@ fn read_numbers_and_boards(input: &String) -> (Vec<i64>, Vec<Board>) {
 /// The counters in the last row and column make it easier to look for winning
 /// rows or columns. The counter in the bottom right-hand entry keeps track of
 /// the sum of unmarked numbers
-fn mark_boards(number: i64, boards: &mut Vec<Board>) {
-    for (_n, board) in boards.iter_mut().enumerate() {
+pub fn mark_boards(number: i64, boards: &mut Vec<Board>) {
+    for board in boards.iter_mut() {
         // Seems like rust ndarray has no easy way of finding the multi-index of
         // an element (other than via nested .find or .position calls on the
         // respective iterator), so just gonna do this manually ...
@@ -80,7 +107,7 @@ fn mark_boards(number: i64, boards: &mut Vec<Board>) {
                 board[[i, 5]] += 1;
                 board[[5, j]] += 1;
                 board[[5, 5]] -= number;
-                // println!("\nFound {} at ({}, {}) on board {}:\n{}", number, i, j, _n, board);
+                // println!("\nFound {} at ({}, {}) on board:\n{}", number, i, j, board);
                 break;
             }
         }
@@ -88,7 +115,7 @@ fn mark_boards(number: i64, boards: &mut Vec<Board>) {
 }
 
 /// Checks whether there is a bingo on the given board
-fn has_bingo(board: &Board) -> bool {
+pub fn has_bingo(board: &Board) -> bool {
     board.slice(s![-1, 0..5]).iter().any(|&v| v == 5)
         || board.slice(s![0..5, -1]).iter().any(|&v| v == 5)
 }
@@ -96,7 +123,7 @@ fn has_bingo(board: &Board) -> bool {
 /// Finds winners and keeps track of their scores in the winners map
 ///
 /// Returns the board numbers of the newly winning boards
-fn find_winners(number: i64, boards: &Vec<Board>, winners: &mut Winners) -> Vec<usize> {
+pub fn find_winners(number: i64, boards: &Vec<Board>, winners: &mut Winners) -> Vec<usize> {
     let mut new_winners = Vec::new();
 
     for (n, board) in boards.iter().enumerate() {
@@ -104,7 +131,7 @@ fn find_winners(number: i64, boards: &Vec<Board>, winners: &mut Winners) -> Vec<
             continue;
         }
 
-        if has_bingo(&board) {
+        if has_bingo(board) {
             new_winners.push(n);
             winners.insert(n, number * board[[5, 5]]);
         }
@@ -116,9 +143,13 @@ fn find_winners(number: i64, boards: &Vec<Board>, winners: &mut Winners) -> Vec<
 // -----------------------------------------------------------------------------
 
 /// Implements the solution for part 1
-pub fn solve_part1(input: &String, _input_mode: &InputMode) -> i64 {
-    let (numbers, mut boards) = read_numbers_and_boards(input);
-    println!(
+pub fn solve_part1(
+    (numbers, boards): &(Vec<i64>, Vec<Board>),
+    ctx: &Context,
+) -> Result<Answer, AocError> {
+    let mut boards = boards.clone(); // will be marked
+    verbose!(
+        ctx,
         "Have {} numbers and {} boards.",
         numbers.len(),
         boards.len()
@@ -129,28 +160,35 @@ pub fn solve_part1(input: &String, _input_mode: &InputMode) -> i64 {
     let mut new_winners = Vec::new();
 
     for (n, number) in numbers.iter().enumerate() {
-        println!("Draw #{:2} yields:  {:2}", n, number);
+        debug!(ctx, "Draw #{:2} yields:  {:2}", n, number);
 
         mark_boards(*number, &mut boards);
         new_winners = find_winners(*number, &boards, &mut winners);
 
-        if winners.len() > 0 {
-            println!("  Bingo! on boards:  {:?}", new_winners);
+        if !winners.is_empty() {
+            verbose!(ctx, "  Bingo! on boards:  {:?}", new_winners);
             break;
         }
     }
 
     if new_winners.len() != 1 {
-        panic!("Expected single first winner, got: {:?}", new_winners);
+        return Err(AocError::NoSolution(format!(
+            "expected single first winner, got: {:?}",
+            new_winners
+        )));
     }
 
-    return *winners.get(&new_winners[0]).unwrap() as i64;
+    return Ok(winners[&new_winners[0]].into());
 }
 
 /// Implements the solution for part 2
-pub fn solve_part2(input: &String, _input_mode: &InputMode) -> i64 {
-    let (numbers, mut boards) = read_numbers_and_boards(input);
-    println!(
+pub fn solve_part2(
+    (numbers, boards): &(Vec<i64>, Vec<Board>),
+    ctx: &Context,
+) -> Result<Answer, AocError> {
+    let mut boards = boards.clone(); // will be marked
+    verbose!(
+        ctx,
         "Have {} numbers and {} boards.",
         numbers.len(),
         boards.len()
@@ -161,23 +199,48 @@ pub fn solve_part2(input: &String, _input_mode: &InputMode) -> i64 {
     let mut new_winners = Vec::new();
 
     for (n, number) in numbers.iter().enumerate() {
-        println!("Draw #{:2} yields:  {:2}", n, number);
+        debug!(ctx, "Draw #{:2} yields:  {:2}", n, number);
 
         mark_boards(*number, &mut boards);
         new_winners = find_winners(*number, &boards, &mut winners);
 
-        if new_winners.len() > 0 {
-            println!("  new Bingo! on boards:  {:?}", new_winners);
+        if !new_winners.is_empty() {
+            verbose!(ctx, "  new Bingo! on boards:  {:?}", new_winners);
         }
         if winners.len() == boards.len() {
-            println!("\nAll bingo!");
+            verbose!(ctx, "\nAll bingo!");
             break;
         }
     }
 
-    if new_winners.len() != 1 {
-        panic!("Expected single last winner, got: {:?}", new_winners);
+    if new_winners.len() != 1 || winners.len() != boards.len() {
+        return Err(AocError::NoSolution(format!(
+            "expected single last winner, got: {:?}",
+            new_winners
+        )));
+    }
+
+    return Ok(winners[&new_winners[0]].into());
+}
+
+// -----------------------------------------------------------------------------
+
+/// The solution for day 04
+pub struct Day04;
+
+impl Solution for Day04 {
+    const DAY: u8 = DAY;
+    type Input = (Vec<i64>, Vec<Board>);
+
+    fn parse(input: &String) -> Result<(Vec<i64>, Vec<Board>), AocError> {
+        read_numbers_and_boards(input)
+    }
+
+    fn part1(input: &(Vec<i64>, Vec<Board>), ctx: &Context) -> Result<Answer, AocError> {
+        solve_part1(input, ctx)
     }
 
-    return *winners.get(&new_winners[0]).unwrap() as i64;
+    fn part2(input: &(Vec<i64>, Vec<Board>), ctx: &Context) -> Result<Answer, AocError> {
+        solve_part2(input, ctx)
+    }
 }
@@ -6,19 +6,27 @@ use lazy_static::lazy_static;
 use regex::Regex;
 use std::cmp::max;
 use std::fmt;
+use std::io::BufRead;
 
 #[allow(unused_imports)]
 use ndarray::prelude::*;
 
-use crate::InputMode;
+use crate::solutions::stream_lines;
+use crate::{debug, verbose, Answer, AocError, Context, Solution, StreamFunc};
 
-type Seafloor = ndarray::Array2<i64>;
+const DAY: u8 = 5;
+
+/// The number of lines crossing each point of the seafloor, indexed by `[y, x]`
+pub type Seafloor = ndarray::Array2<i64>;
+
+/// The maximum number of points of a seafloor map, i.e. 8192 x 8192
+pub const MAX_SEAFLOOR_POINTS: usize = 1 << 26;
 
 /// A point, i.e. coordinates in the x-y seafloor plane
 #[derive(Debug)]
-struct Point {
-    x: usize,
-    y: usize,
+pub struct Point {
+    pub x: usize,
+    pub y: usize,
 }
 
 impl fmt::Display for Point {
@@ -32,21 +40,21 @@ impl fmt::Display for Point {
 /// Implementation includes first steps in building structs and methods to
 /// solve this problem ... perhaps a bit overkill for this, though
 #[derive(Debug)]
-struct Line {
-    src: Point,
-    dest: Point,
+pub struct Line {
+    pub src: Point,
+    pub dest: Point,
 }
 
 impl Line {
-    fn is_horizontal(&self) -> bool {
+    pub fn is_horizontal(&self) -> bool {
         self.src.y == self.dest.y
     }
 
-    fn is_vertical(&self) -> bool {
+    pub fn is_vertical(&self) -> bool {
         self.src.x == self.dest.x
     }
 
-    fn is_diagonal(&self) -> bool {
+    pub fn is_diagonal(&self) -> bool {
         (self.src.x as i64 - self.dest.x as i64).abs()
             == (self.src.y as i64 - self.dest.y as i64).abs()
     }
@@ -54,7 +62,7 @@ impl Line {
     /// Iterates over x-coordinates of this line from source to destination
     ///
     /// Uses type erasure to allow forward or reverse iteration
-    fn x_iter(&self) -> Box<dyn Iterator<Item = usize>> {
+    pub fn x_iter(&self) -> Box<dyn Iterator<Item = usize>> {
         if self.src.x <= self.dest.x {
             return Box::new(self.src.x..=self.dest.x) as Box<dyn Iterator<Item = usize>>;
         }
@@ -64,7 +72,7 @@ impl Line {
     /// Iterates over y-coordinates of this line from source to destination
     ///
     /// Uses type erasure to allow forward or reverse iteration
-    fn y_iter(&self) -> Box<dyn Iterator<Item = usize>> {
+    pub fn y_iter(&self) -> Box<dyn Iterator<Item = usize>> {
         if self.src.y <= self.dest.y {
             return Box::new(self.src.y..=self.dest.y) as Box<dyn Iterator<Item = usize>>;
         }
@@ -72,35 +80,28 @@ impl Line {
     }
 
     /// Returns the points between source and destination (inclusive)
-    fn points(&self, incl_diag: bool) -> Vec<Point> {
+    ///
+    /// Expects the line to be horizontal, vertical, or diagonal, which is
+    /// ensured when parsing it.
+    pub fn points(&self, incl_diag: bool) -> Vec<Point> {
         let mut pts = Vec::new();
 
         if self.is_horizontal() {
             for x in self.x_iter() {
-                pts.push(Point {
-                    x: x,
-                    y: self.src.y,
-                });
+                pts.push(Point { x, y: self.src.y });
             }
         } else if self.is_vertical() {
             for y in self.y_iter() {
-                pts.push(Point {
-                    x: self.src.x,
-                    y: y,
-                });
+                pts.push(Point { x: self.src.x, y });
             }
-        } else if self.is_diagonal() {
-            if incl_diag {
-                // println!("Line {}:", self);
-                for (x, y) in self.x_iter().zip(self.y_iter()) {
-                    // println!("  ({}, {})", x, y);
-                    pts.push(Point { x: x, y: y });
-                }
+        } else if incl_diag {
+            // println!("Line {}:", self);
+            for (x, y) in self.x_iter().zip(self.y_iter()) {
+                // println!("  ({}, {})", x, y);
+                pts.push(Point { x, y });
             }
-            // else: do not add any points
-        } else {
-            panic!("Line {} is not horizontal, vertical, or diagonal!", self);
         }
+        // else: diagonal line, but do not add any points
 
         return pts;
     }
@@ -113,82 +114,195 @@ impl fmt::Display for Line {
 }
 // -----------------------------------------------------------------------------
 
-fn parse_line(line: &str) -> Line {
+/// Parses a line like `0,9 -> 5,9`; expects a 0-based line number
+pub fn parse_line((line_no, line): (usize, &str)) -> Result<Line, AocError> {
     lazy_static! {
-        static ref PATTERN: Regex = Regex::new(r"(\d+),(\d+) -> (\d+),(\d+)").unwrap();
+        static ref PATTERN: Regex = Regex::new(r"^(\d+),(\d+) -> (\d+),(\d+)$").unwrap();
     }
-    let cap = PATTERN.captures(line).unwrap();
-    Line {
+    let parse_error = |reason: &str| AocError::parse(DAY, line_no + 1, line, reason);
+
+    let cap = match PATTERN.captures(line.trim()) {
+        Some(cap) => cap,
+        None => return Err(parse_error("expected format `x1,y1 -> x2,y2`")),
+    };
+    let coord = |i: usize| {
+        cap[i]
+            .parse::<usize>()
+            .map_err(|_| parse_error("coordinate out of range"))
+    };
+    let parsed = Line {
         src: Point {
-            x: cap[1].parse().unwrap(),
-            y: cap[2].parse().unwrap(),
+            x: coord(1)?,
+            y: coord(2)?,
         },
         dest: Point {
-            x: cap[3].parse().unwrap(),
-            y: cap[4].parse().unwrap(),
+            x: coord(3)?,
+            y: coord(4)?,
         },
+    };
+
+    if !(parsed.is_horizontal() || parsed.is_vertical() || parsed.is_diagonal()) {
+        return Err(parse_error("line is not horizontal, vertical, or diagonal"));
     }
+    return Ok(parsed);
 }
 
-fn mark_lines(lines: &Vec<Line>, seafloor: &mut Seafloor, incl_diag: bool) {
+/// Parses all lines of the input into seafloor lines
+pub fn read_lines(input: &String) -> Result<Vec<Line>, AocError> {
+    input.lines().enumerate().map(parse_line).collect()
+}
+
+/// Marks the points of a line on the seafloor
+pub fn mark_line(line: &Line, seafloor: &mut Seafloor, incl_diag: bool) {
+    for pt in line.points(incl_diag) {
+        seafloor[[pt.y, pt.x]] += 1;
+    }
+}
+
+/// Marks the points of all lines on the seafloor
+pub fn mark_lines(lines: &Vec<Line>, seafloor: &mut Seafloor, incl_diag: bool) {
     for line in lines {
-        for pt in line.points(incl_diag) {
-            seafloor[[pt.y, pt.x]] += 1;
-        }
+        mark_line(line, seafloor, incl_diag);
         // println!("Marked line {} ... Seafloor is now:\n{}\n", line, seafloor);
     }
 }
 
-/// Marks
-fn mark_seafloor(lines: &Vec<Line>, incl_diag: bool, _input_mode: &InputMode) -> Seafloor {
+/// The size of a square seafloor map just large enough to hold a line
+fn required_size(line: &Line) -> usize {
+    max(line.src.x, max(line.src.y, max(line.dest.x, line.dest.y))) + 1
+}
+
+/// Checks that a square seafloor map of the given size does not exceed
+/// [`MAX_SEAFLOOR_POINTS`]
+fn check_seafloor_size(size: usize) -> Result<(), AocError> {
+    match size.checked_mul(size) {
+        Some(num_points) if num_points <= MAX_SEAFLOOR_POINTS => Ok(()),
+        _ => Err(AocError::InvalidInput(format!(
+            "seafloor of size {}x{} exceeds the maximum of {} points",
+            size, size, MAX_SEAFLOOR_POINTS
+        ))),
+    }
+}
+
+/// Marks the lines on a seafloor map that is just large enough to hold them
+pub fn mark_seafloor(
+    lines: &Vec<Line>,
+    incl_diag: bool,
+    ctx: &Context,
+) -> Result<Seafloor, AocError> {
     // Find the size of the seafloor
-    let mut max_coord = 0;
+    let mut size = 0;
     for line in lines {
-        if matches!(_input_mode, InputMode::Test) {
-            println!("  {}", line);
-        }
-        max_coord = max(
-            max_coord,
-            max(line.src.x, max(line.src.y, max(line.dest.x, line.dest.y))),
-        );
+        debug!(ctx, "  {}", line);
+        size = max(size, required_size(line));
     }
 
     // Construct the seafloor map to a suitable size
-    let mut seafloor = Seafloor::zeros((max_coord + 1, max_coord + 1));
-    println!(
+    check_seafloor_size(size)?;
+    let mut seafloor = Seafloor::zeros((size, size));
+    verbose!(
+        ctx,
         "Have {} lines on seafloor of shape {:?} ...",
         lines.len(),
         seafloor.shape()
     );
 
     // Now mark the lines on the seafloor
-    println!("Now marking lines (incl_diag: {}) ...", incl_diag);
-    mark_lines(&lines, &mut seafloor, incl_diag);
+    verbose!(ctx, "Now marking lines (incl_diag: {}) ...", incl_diag);
+    mark_lines(lines, &mut seafloor, incl_diag);
 
-    if matches!(_input_mode, InputMode::Test) {
-        println!("Seafloor is now marked:\n{}", seafloor);
+    debug!(ctx, "Seafloor is now marked:\n{}", seafloor);
+    return Ok(seafloor);
+}
+
+/// Marks the lines read one at a time on a seafloor map, which is grown as
+/// needed, such that only the map is held in memory
+pub fn mark_seafloor_stream(
+    reader: &mut dyn BufRead,
+    incl_diag: bool,
+    ctx: &Context,
+) -> Result<Seafloor, AocError> {
+    let mut seafloor = Seafloor::zeros((0, 0));
+    let mut num_lines = 0;
+
+    verbose!(ctx, "Now marking lines (incl_diag: {}) ...", incl_diag);
+    for line in stream_lines(reader) {
+        let (line_no, line) = line?;
+        let line = parse_line((line_no, &line))?;
+        debug!(ctx, "  {}", line);
+
+        // Grow the map to powers of two, to copy it only rarely; as the maximum
+        // is a power of four, this never exceeds it
+        let size = seafloor.nrows();
+        if required_size(&line) > size {
+            check_seafloor_size(required_size(&line))?;
+            let new_size = required_size(&line).next_power_of_two();
+            let mut grown = Seafloor::zeros((new_size, new_size));
+            grown.slice_mut(s![..size, ..size]).assign(&seafloor);
+            seafloor = grown;
+        }
+        mark_line(&line, &mut seafloor, incl_diag);
+        num_lines += 1;
     }
-    return seafloor;
+    verbose!(
+        ctx,
+        "Have marked {} lines on seafloor of shape {:?} ...",
+        num_lines,
+        seafloor.shape()
+    );
+
+    debug!(ctx, "Seafloor is now marked:\n{}", seafloor);
+    return Ok(seafloor);
+}
+
+/// Counts the points of the seafloor where at least two lines overlap
+pub fn count_overlaps(seafloor: &Seafloor) -> Answer {
+    let num_overlaps = seafloor.iter().filter(|&h| *h >= 2).count() as i64;
+    return num_overlaps.into();
 }
 
 // -----------------------------------------------------------------------------
 
 /// Implements the solution for part 1
-pub fn solve_part1(input: &String, _input_mode: &InputMode) -> i64 {
-    println!("Parsing lines ...");
-    let lines = input.lines().map(parse_line).collect::<Vec<Line>>();
-    mark_seafloor(&lines, false, _input_mode)
-        .iter()
-        .filter(|&h| *h >= 2)
-        .count() as i64
+pub fn solve_part1(lines: &Vec<Line>, ctx: &Context) -> Result<Answer, AocError> {
+    return Ok(count_overlaps(&mark_seafloor(lines, false, ctx)?));
 }
 
 /// Implements the solution for part 2
-pub fn solve_part2(input: &String, _input_mode: &InputMode) -> i64 {
-    println!("Parsing lines ...");
-    let lines = input.lines().map(parse_line).collect::<Vec<Line>>();
-    mark_seafloor(&lines, true, _input_mode)
-        .iter()
-        .filter(|&h| *h >= 2)
-        .count() as i64
+pub fn solve_part2(lines: &Vec<Line>, ctx: &Context) -> Result<Answer, AocError> {
+    return Ok(count_overlaps(&mark_seafloor(lines, true, ctx)?));
+}
+
+/// Implements the solution for part 1 on the input read line by line
+pub fn stream_part1(reader: &mut dyn BufRead, ctx: &Context) -> Result<Answer, AocError> {
+    return Ok(count_overlaps(&mark_seafloor_stream(reader, false, ctx)?));
+}
+
+/// Implements the solution for part 2 on the input read line by line
+pub fn stream_part2(reader: &mut dyn BufRead, ctx: &Context) -> Result<Answer, AocError> {
+    return Ok(count_overlaps(&mark_seafloor_stream(reader, true, ctx)?));
+}
+
+// -----------------------------------------------------------------------------
+
+/// The solution for day 05
+pub struct Day05;
+
+impl Solution for Day05 {
+    const DAY: u8 = DAY;
+    type Input = Vec<Line>;
+
+    fn parse(input: &String) -> Result<Vec<Line>, AocError> {
+        read_lines(input)
+    }
+
+    fn part1(input: &Vec<Line>, ctx: &Context) -> Result<Answer, AocError> {
+        solve_part1(input, ctx)
+    }
+
+    fn part2(input: &Vec<Line>, ctx: &Context) -> Result<Answer, AocError> {
+        solve_part2(input, ctx)
+    }
+
+    const STREAMING: Option<[StreamFunc; 2]> = Some([stream_part1, stream_part2]);
 }
@@ -1,9 +1,33 @@
 //! # Solutions for Day 06 - Lanternfish
 //!
 //! For puzzle text, see https://adventofcode.com/2021/day/6
-use crate::InputMode;
+use crate::{Answer, AocError, Context, Solution};
 
-fn iterate_age_distr(age_distr: &mut [i64; 9], n: u64) {
+const DAY: u8 = 6;
+
+/// Parses the comma-separated ages into an age distribution
+pub fn read_age_distr(input: &String) -> Result<[u128; 9], AocError> {
+    let mut age_distr: [u128; 9] = [0, 0, 0, 0, 0, 0, 0, 0, 0];
+    let line = input.trim();
+    for age in line.split(",") {
+        match age.parse::<usize>() {
+            Ok(age) if age < 9 => age_distr[age] += 1,
+            Ok(age) => {
+                return Err(AocError::parse(
+                    DAY,
+                    1,
+                    line,
+                    format!("invalid age {}", age),
+                ))
+            }
+            Err(err) => return Err(AocError::parse(DAY, 1, line, err)),
+        }
+    }
+    return Ok(age_distr);
+}
+
+/// Evolves the age distribution of the lanternfish population by `n` days
+pub fn iterate_age_distr(age_distr: &mut [u128; 9], n: u64) {
     let mut num_procreating;
 
     for _ in 0..n {
@@ -15,23 +39,39 @@ fn iterate_age_distr(age_distr: &mut [i64; 9], n: u64) {
 }
 
 /// Implements the solution for part 1
-pub fn solve_part1(input: &String, _input_mode: &InputMode) -> i64 {
-    let mut age_distr: [i64; 9] = [0, 0, 0, 0, 0, 0, 0, 0, 0];
-    for age in input.trim().split(",").map(|n| n.parse::<usize>().unwrap()) {
-        age_distr[age] += 1;
-    }
+pub fn solve_part1(age_distr: &[u128; 9], _ctx: &Context) -> Result<Answer, AocError> {
+    let mut age_distr = *age_distr;
 
     iterate_age_distr(&mut age_distr, 80);
-    return age_distr.iter().sum();
+    return Ok(Answer::from_unsigned(age_distr.iter().sum()));
 }
 
 /// Implements the solution for part 2
-pub fn solve_part2(input: &String, _input_mode: &InputMode) -> i64 {
-    let mut age_distr: [i64; 9] = [0, 0, 0, 0, 0, 0, 0, 0, 0];
-    for age in input.trim().split(",").map(|n| n.parse::<usize>().unwrap()) {
-        age_distr[age] += 1;
-    }
+pub fn solve_part2(age_distr: &[u128; 9], _ctx: &Context) -> Result<Answer, AocError> {
+    let mut age_distr = *age_distr;
 
     iterate_age_distr(&mut age_distr, 256);
-    return age_distr.iter().sum();
+    return Ok(Answer::from_unsigned(age_distr.iter().sum()));
+}
+
+// -----------------------------------------------------------------------------
+
+/// The solution for day 06
+pub struct Day06;
+
+impl Solution for Day06 {
+    const DAY: u8 = DAY;
+    type Input = [u128; 9];
+
+    fn parse(input: &String) -> Result<[u128; 9], AocError> {
+        read_age_distr(input)
+    }
+
+    fn part1(input: &[u128; 9], ctx: &Context) -> Result<Answer, AocError> {
+        solve_part1(input, ctx)
+    }
+
+    fn part2(input: &[u128; 9], ctx: &Context) -> Result<Answer, AocError> {
+        solve_part2(input, ctx)
+    }
 }
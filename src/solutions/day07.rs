@@ -4,35 +4,45 @@
 
 use std::cmp;
 
-use crate::InputMode;
+use crate::{verbose, Answer, AocError, Context, Solution};
 
-/// Implements the solution for part 1
-pub fn solve_part1(input: &String, _input_mode: &InputMode) -> i64 {
-    let mut positions = input
-        .trim()
+const DAY: u8 = 7;
+
+/// Parses the comma-separated crab positions and returns them sorted
+pub fn read_sorted_positions(input: &String) -> Result<Vec<i64>, AocError> {
+    let line = input.trim();
+    let mut positions = line
         .split(",")
-        .map(|n| n.parse::<i64>().unwrap())
-        .collect::<Vec<i64>>();
+        .map(|n| {
+            n.parse::<i64>()
+                .map_err(|err| AocError::parse(DAY, 1, line, err))
+        })
+        .collect::<Result<Vec<i64>, AocError>>()?;
     positions.sort();
+    return Ok(positions);
+}
 
+/// Implements the solution for part 1
+pub fn solve_part1(positions: &Vec<i64>, ctx: &Context) -> Result<Answer, AocError> {
     let n = positions.len();
-    println!("Have {} crab positions available ...", n);
+    verbose!(ctx, "Have {} crab positions available ...", n);
 
     // Target position is simply the median
-    let target_position = match n % 2 == 0 {
+    let target_position = match n.is_multiple_of(2) {
         true => ((positions[n / 2 - 1] + positions[n / 2]) as f64 / 2.).round() as i64,
         false => positions[n / 2],
     };
 
     // Compute fuel consumption
-    positions
+    Ok(positions
         .iter()
         .map(|pos| (pos - target_position).abs())
-        .sum()
+        .sum::<i64>()
+        .into())
 }
 
 /// Computes the non-linear fuel consumption used in part 2
-fn compute_fuel(pos: i64, target: i64) -> i64 {
+pub fn compute_fuel(pos: i64, target: i64) -> i64 {
     let mut f = 0;
     for n in 1..=(pos - target).abs() {
         f += n;
@@ -41,16 +51,9 @@ fn compute_fuel(pos: i64, target: i64) -> i64 {
 }
 
 /// Implements the solution for part 2
-pub fn solve_part2(input: &String, _input_mode: &InputMode) -> i64 {
-    let mut positions = input
-        .trim()
-        .split(",")
-        .map(|n| n.parse::<i64>().unwrap())
-        .collect::<Vec<i64>>();
-    positions.sort();
-
+pub fn solve_part2(positions: &Vec<i64>, ctx: &Context) -> Result<Answer, AocError> {
     let n = positions.len();
-    println!("Have {} crab positions available ...", n);
+    verbose!(ctx, "Have {} crab positions available ...", n);
 
     // Best guess for optimal target position: mean value
     let mean_pos: i64 = positions.iter().sum::<i64>() / n as i64;
@@ -67,5 +70,27 @@ pub fn solve_part2(input: &String, _input_mode: &InputMode) -> i64 {
         );
     }
 
-    return minimum_fuel_consumption;
+    return Ok(minimum_fuel_consumption.into());
+}
+
+// -----------------------------------------------------------------------------
+
+/// The solution for day 07
+pub struct Day07;
+
+impl Solution for Day07 {
+    const DAY: u8 = DAY;
+    type Input = Vec<i64>;
+
+    fn parse(input: &String) -> Result<Vec<i64>, AocError> {
+        read_sorted_positions(input)
+    }
+
+    fn part1(input: &Vec<i64>, ctx: &Context) -> Result<Answer, AocError> {
+        solve_part1(input, ctx)
+    }
+
+    fn part2(input: &Vec<i64>, ctx: &Context) -> Result<Answer, AocError> {
+        solve_part2(input, ctx)
+    }
 }
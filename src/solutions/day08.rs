@@ -2,37 +2,266 @@
 //!
 //! For puzzle text, see https://adventofcode.com/2021/day/8
 
-use crate::InputMode;
+use itertools::Itertools;
 
-/// Implements the solution for part 1
-pub fn solve_part1(input: &String, _input_mode: &InputMode) -> i64 {
-    // Parse data into a vector of vectors of output segment strings
-    let all_outputs = input
-        .trim()
-        .lines()
-        .map(|line| {
-            line.split("|").collect::<Vec<_>>()[1]
-                .trim()
-                .split(" ")
-                .collect::<Vec<_>>()
-        })
-        .collect::<Vec<_>>();
+use crate::{debug, Answer, AocError, Context, Solution};
+
+const DAY: u8 = 8;
+
+/// The segments lit up for each digit, with segment `a` as bit 0, `b` as bit 1 etc.
+pub const DIGIT_SEGMENTS: [u8; 10] = [
+    0b1110111, // 0: abc efg
+    0b0100100, // 1:   c  f
+    0b1011101, // 2: a cde g
+    0b1101101, // 3: a cd fg
+    0b0101110, // 4:  bcd f
+    0b1101011, // 5: ab d fg
+    0b1111011, // 6: ab defg
+    0b0100101, // 7: a c  f
+    0b1111111, // 8: abcdefg
+    0b1101111, // 9: abcd fg
+];
+
+/// Converts a string of wire letters into a bit mask, `a` being bit 0
+pub fn wires2mask(wires: &str) -> Result<u8, String> {
+    let mut mask = 0;
+    for c in wires.chars() {
+        match c {
+            'a'..='g' => mask |= 1 << (c as u8 - b'a'),
+            _ => return Err(format!("invalid wire character {:?}", c)),
+        }
+    }
+    return Ok(mask);
+}
+
+/// Splits an entry into the signal patterns and output values, i.e. the
+/// whitespace-separated parts before and after the `|`
+pub fn split_entry(line_no: usize, line: &str) -> Result<(Vec<&str>, Vec<&str>), AocError> {
+    match line.split_once("|") {
+        Some((patterns, outputs)) => Ok((
+            patterns.split_whitespace().collect(),
+            outputs.split_whitespace().collect(),
+        )),
+        None => Err(AocError::parse(DAY, line_no, line, "missing `|` separator")),
+    }
+}
+
+/// A display entry, i.e. the unique signal patterns and the output values,
+/// each encoded as a wire mask
+pub struct Entry {
+    pub patterns: Vec<u8>,
+    pub outputs: Vec<u8>,
+}
+
+/// The number of unique signal patterns of an entry, one per digit
+pub const NUM_PATTERNS: usize = 10;
+
+/// The number of output values of an entry
+pub const NUM_OUTPUTS: usize = 4;
+
+/// Parses all lines of the input into display entries
+///
+/// Each entry is required to consist of ten distinct signal patterns and four
+/// output values, since an incomplete entry may fit several wirings.
+pub fn read_entries(input: &String) -> Result<Vec<Entry>, AocError> {
+    let mut entries = Vec::new();
+
+    for (n, line) in input.trim().lines().enumerate() {
+        let (patterns, outputs) = split_entry(n + 1, line)?;
+        let to_masks = |wires: Vec<&str>| {
+            wires
+                .into_iter()
+                .map(wires2mask)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|reason| AocError::parse(DAY, n + 1, line, reason))
+        };
+        let entry = Entry {
+            patterns: to_masks(patterns)?,
+            outputs: to_masks(outputs)?,
+        };
+
+        let parse_error = |reason: String| AocError::parse(DAY, n + 1, line, reason);
+        if entry.patterns.len() != NUM_PATTERNS {
+            return Err(parse_error(format!(
+                "expected {} signal patterns, got {}",
+                NUM_PATTERNS,
+                entry.patterns.len()
+            )));
+        }
+        if entry.patterns.iter().unique().count() != NUM_PATTERNS {
+            return Err(parse_error(String::from("signal patterns are not distinct")));
+        }
+        if entry.outputs.len() != NUM_OUTPUTS {
+            return Err(parse_error(format!(
+                "expected {} output values, got {}",
+                NUM_OUTPUTS,
+                entry.outputs.len()
+            )));
+        }
+        entries.push(entry);
+    }
+
+    return Ok(entries);
+}
+
+/// Maps a wire mask to a segment mask using the given wire-to-segment wiring
+pub fn apply_wiring(wires: u8, wiring: &[usize]) -> u8 {
+    let mut segments = 0;
+    for (wire, segment) in wiring.iter().enumerate() {
+        if wires & (1 << wire) != 0 {
+            segments |= 1 << segment;
+        }
+    }
+    return segments;
+}
+
+/// Looks up the digit that is displayed by the given segment mask
+pub fn segments2digit(segments: u8) -> Option<i64> {
+    DIGIT_SEGMENTS
+        .iter()
+        .position(|&s| s == segments)
+        .map(|d| d as i64)
+}
+
+/// Deduces the wire-to-segment wiring from the ten unique signal patterns
+///
+/// Simply tries out all 5040 possible permutations and returns the first one
+/// that maps each of the patterns onto a valid digit.
+pub fn deduce_wiring(patterns: &[u8]) -> Option<Vec<usize>> {
+    (0..7).permutations(7).find(|wiring| {
+        patterns
+            .iter()
+            .all(|&p| segments2digit(apply_wiring(p, wiring)).is_some())
+    })
+}
+
+// -----------------------------------------------------------------------------
 
+/// Implements the solution for part 1
+pub fn solve_part1(entries: &Vec<Entry>, _ctx: &Context) -> Result<Answer, AocError> {
     // Count number of appearances of "easy digits" of length 2, 3, 4, 7
-    let easy_digit_lengths = vec![2, 3, 4, 7];
-    let mut num_easy_digits = 0;
-    for outputs in all_outputs {
-        for output in outputs {
-            if easy_digit_lengths.contains(&output.len()) {
+    let easy_digit_lengths = [2, 3, 4, 7];
+    let mut num_easy_digits: i64 = 0;
+    for entry in entries {
+        for output in entry.outputs.iter() {
+            if easy_digit_lengths.contains(&output.count_ones()) {
                 num_easy_digits += 1;
             }
         }
     }
 
-    return num_easy_digits;
+    return Ok(num_easy_digits.into());
 }
 
 /// Implements the solution for part 2
-pub fn solve_part2(input: &String, _input_mode: &InputMode) -> i64 {
-    0
+pub fn solve_part2(entries: &Vec<Entry>, ctx: &Context) -> Result<Answer, AocError> {
+    let mut sum_of_outputs = 0;
+
+    for (n, entry) in entries.iter().enumerate() {
+        let wiring = match deduce_wiring(&entry.patterns) {
+            Some(wiring) => wiring,
+            None => {
+                return Err(AocError::NoSolution(format!(
+                    "no consistent wiring for entry {}",
+                    n + 1
+                )))
+            }
+        };
+
+        // Decode the output digits into a single number
+        let mut output_value = 0;
+        for &output in entry.outputs.iter() {
+            let digit = match segments2digit(apply_wiring(output, &wiring)) {
+                Some(digit) => digit,
+                None => {
+                    return Err(AocError::NoSolution(format!(
+                        "output of entry {} does not match any digit",
+                        n + 1
+                    )))
+                }
+            };
+            output_value = 10 * output_value + digit;
+        }
+        debug!(ctx, "Entry {:3}  ->  {}", n + 1, output_value);
+
+        sum_of_outputs += output_value;
+    }
+
+    return Ok(sum_of_outputs.into());
+}
+
+// -----------------------------------------------------------------------------
+
+/// The solution for day 08
+pub struct Day08;
+
+impl Solution for Day08 {
+    const DAY: u8 = DAY;
+    type Input = Vec<Entry>;
+
+    fn parse(input: &String) -> Result<Vec<Entry>, AocError> {
+        read_entries(input)
+    }
+
+    fn part1(input: &Vec<Entry>, ctx: &Context) -> Result<Answer, AocError> {
+        solve_part1(input, ctx)
+    }
+
+    fn part2(input: &Vec<Entry>, ctx: &Context) -> Result<Answer, AocError> {
+        solve_part2(input, ctx)
+    }
+}
+
+// -----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{InputMode, Verbosity};
+
+    const ENTRY: &str =
+        "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf\n";
+
+    fn quiet() -> Context {
+        Context::new(InputMode::Test, Verbosity::Quiet)
+    }
+
+    #[test]
+    fn deduces_wiring_of_entry() {
+        let entries = read_entries(&String::from(ENTRY)).unwrap();
+        let wiring = deduce_wiring(&entries[0].patterns).unwrap();
+        let digits = entries[0]
+            .patterns
+            .iter()
+            .map(|&p| segments2digit(apply_wiring(p, &wiring)).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(digits, vec![8, 5, 2, 3, 7, 9, 6, 4, 0, 1]);
+        assert_eq!(solve_part2(&entries, &quiet()).unwrap().to_string(), "5353");
+    }
+
+    #[test]
+    fn entry_without_consistent_wiring_fails() {
+        // The five-segment pattern `cdfbe` of the digit 5 replaced by `abceg`
+        let input = ENTRY.replacen("cdfbe", "abceg", 1);
+        let entries = read_entries(&input).unwrap();
+        assert_eq!(deduce_wiring(&entries[0].patterns), None);
+        assert!(matches!(
+            solve_part2(&entries, &quiet()),
+            Err(AocError::NoSolution(_))
+        ));
+    }
+
+    #[test]
+    fn incomplete_entries_are_rejected() {
+        for input in [
+            "ab | ab ba\n",
+            "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb\n",
+            "acedgfb acedgfb gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf\n",
+        ] {
+            assert!(matches!(
+                read_entries(&String::from(input)),
+                Err(AocError::Parse { .. })
+            ));
+        }
+    }
 }
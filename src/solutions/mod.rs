@@ -1,10 +1,191 @@
 //! Solutions to the Advent of Code 2021, organized by days
+//!
+//! Each day's module `dayNN` provides a type `DayNN` implementing the
+//! [`Solution`] trait. The modules are collected by the build script, such
+//! that adding a day only requires adding its file `dayNN.rs`. The puzzle
+//! title is taken from the first line of the module docs, which reads
+//! `//! # Solutions for Day NN - <title>`.
 
-pub mod day01;
-pub mod day02;
-pub mod day03;
-pub mod day04;
-pub mod day05;
-pub mod day06;
-pub mod day07;
-pub mod day08;
+use std::any::Any;
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::path::PathBuf;
+
+use crate::{Answer, AocError, Context};
+
+include!(concat!(env!("OUT_DIR"), "/solutions.rs"));
+
+/// The solution of a day's puzzle, split into a parsing and a solving phase
+pub trait Solution {
+    /// The day of the puzzle
+    const DAY: u8;
+
+    /// The parsed puzzle input, shared by both parts
+    type Input: 'static;
+
+    /// Parses the puzzle input
+    fn parse(input: &String) -> Result<Self::Input, AocError>;
+
+    /// Implements the solution for part 1
+    fn part1(input: &Self::Input, ctx: &Context) -> Result<Answer, AocError>;
+
+    /// Implements the solution for part 2
+    fn part2(input: &Self::Input, ctx: &Context) -> Result<Answer, AocError>;
+
+    /// Implements the solutions for both parts on the input read line by line,
+    /// if the solution supports it
+    ///
+    /// Streaming solutions process their input without holding it in memory,
+    /// e.g. large generated inputs; they are preferred when running a day.
+    const STREAMING: Option<[StreamFunc; 2]> = None;
+
+    /// Whether the solutions for both parts are implemented, as opposed to
+    /// the stubs generated when scaffolding a day
+    const IMPLEMENTED: [bool; 2] = [true, true];
+
+    /// Alternative implementations of the same puzzle, e.g. to benchmark them
+    /// against each other
+    fn variants() -> Vec<Variant> {
+        Vec::new()
+    }
+}
+
+// -----------------------------------------------------------------------------
+
+/// Type alias for the parsing phase of a solution, with type-erased output
+pub type ParseFunc = fn(&String) -> Result<Box<dyn Any>, AocError>;
+
+/// Type alias for the solving phase of a solution, receiving the parsed input
+pub type SolveFunc = fn(&dyn Any, &Context) -> Result<Answer, AocError>;
+
+/// Type alias for a solution processing its input line by line
+pub type StreamFunc = fn(&mut dyn BufRead, &Context) -> Result<Answer, AocError>;
+
+/// A solution function, split into a parsing and a solving phase
+///
+/// This allows to time both phases separately or to re-use parsed input.
+/// Solutions may additionally be able to stream their input, see
+/// [`Solution::STREAMING`].
+#[derive(Clone, Copy)]
+pub struct SolutionFunc {
+    pub parse: ParseFunc,
+    pub solve: SolveFunc,
+    pub stream: Option<StreamFunc>,
+}
+
+impl SolutionFunc {
+    /// Parses the input and computes the solution from it
+    pub fn run(&self, input: &String, ctx: &Context) -> Result<Answer, AocError> {
+        let parsed = (self.parse)(input)?;
+        return (self.solve)(parsed.as_ref(), ctx);
+    }
+}
+
+/// Parses the input of a solution, boxing the parsed input
+fn parse_boxed<S: Solution>(input: &String) -> Result<Box<dyn Any>, AocError> {
+    Ok(Box::new(S::parse(input)?))
+}
+
+/// Solves part 1 of a solution given its boxed parsed input
+fn solve_part1<S: Solution>(parsed: &dyn Any, ctx: &Context) -> Result<Answer, AocError> {
+    S::part1(
+        parsed.downcast_ref().expect("Parsed input has wrong type!"),
+        ctx,
+    )
+}
+
+/// Solves part 2 of a solution given its boxed parsed input
+fn solve_part2<S: Solution>(parsed: &dyn Any, ctx: &Context) -> Result<Answer, AocError> {
+    S::part2(
+        parsed.downcast_ref().expect("Parsed input has wrong type!"),
+        ctx,
+    )
+}
+
+/// Iterates over the lines of a streamed input along with their 0-based
+/// line numbers
+pub fn stream_lines(
+    reader: &mut dyn BufRead,
+) -> impl Iterator<Item = Result<(usize, String), AocError>> + '_ {
+    reader.lines().enumerate().map(|(line_no, line)| {
+        line.map(|line| (line_no, line))
+            .map_err(|source| AocError::Io {
+                path: PathBuf::from("<stream>"),
+                source,
+            })
+    })
+}
+
+// -----------------------------------------------------------------------------
+
+/// A type-erased solution in the registry
+#[derive(Clone, Copy)]
+pub struct Registration {
+    pub day: u8,
+    pub title: &'static str,
+    pub parts: [SolutionFunc; 2],
+    pub implemented: [bool; 2],
+    pub variants: fn() -> Vec<Variant>,
+}
+
+/// Constructs the type-erased solution functions for both parts of a solution
+fn parts_of<S: Solution>() -> [SolutionFunc; 2] {
+    [
+        SolutionFunc {
+            parse: parse_boxed::<S>,
+            solve: solve_part1::<S>,
+            stream: S::STREAMING.map(|funcs| funcs[0]),
+        },
+        SolutionFunc {
+            parse: parse_boxed::<S>,
+            solve: solve_part2::<S>,
+            stream: S::STREAMING.map(|funcs| funcs[1]),
+        },
+    ]
+}
+
+impl Registration {
+    /// Constructs the registration of a solution with the puzzle title
+    pub fn of<S: Solution>(title: &'static str) -> Registration {
+        Registration {
+            day: S::DAY,
+            title,
+            parts: parts_of::<S>(),
+            implemented: S::IMPLEMENTED,
+            variants: S::variants,
+        }
+    }
+}
+
+/// A named alternative implementation of a registered solution
+#[derive(Clone, Copy)]
+pub struct Variant {
+    pub name: &'static str,
+    pub parts: [SolutionFunc; 2],
+}
+
+impl Variant {
+    /// Constructs a variant from another solution of the same day
+    pub fn of<S: Solution>(name: &'static str) -> Variant {
+        Variant {
+            name,
+            parts: parts_of::<S>(),
+        }
+    }
+}
+
+/// Returns the registrations of all available solutions, ordered by day
+pub fn get_solutions() -> Vec<Registration> {
+    registrations()
+}
+
+/// Constructs a map of all available solution functions
+pub fn get_solution_functions() -> HashMap<(u8, u8), SolutionFunc> {
+    let mut funcs: HashMap<(u8, u8), SolutionFunc> = HashMap::new();
+    for registration in registrations() {
+        for (part, func) in (1..).zip(registration.parts) {
+            funcs.insert((registration.day, part), func);
+        }
+    }
+    return funcs;
+}
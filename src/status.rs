@@ -0,0 +1,84 @@
+//! Implementation status of all days, derived from the solution registry and
+//! the input directory
+//!
+//! Whether a registered part is a stub is declared by its solution via
+//! [`Solution::IMPLEMENTED`](crate::Solution::IMPLEMENTED), which the
+//! scaffolded stubs set to `false`.
+
+use std::fmt;
+use std::fs;
+
+use crate::answers::Answers;
+use crate::utils::input_path;
+use crate::{get_solutions, AocError, Context, InputMode, Verbosity};
+
+/// The status of the solution of a part
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PartStatus {
+    /// There is no solution registered for the part
+    Missing,
+    /// The solution is registered, but not implemented yet
+    Stub,
+    /// The solution is implemented
+    Done,
+}
+
+impl fmt::Display for PartStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PartStatus::Missing => write!(f, "-"),
+            PartStatus::Stub => write!(f, "stub"),
+            PartStatus::Done => write!(f, "done"),
+        }
+    }
+}
+
+/// The status of a day, i.e. of its solutions, inputs, and recorded answers
+pub struct DayStatus {
+    pub day: u8,
+    pub title: Option<&'static str>,
+    pub parts: [PartStatus; 2],
+    pub test_input: bool,
+    pub full_input: bool,
+    pub test_answers: [bool; 2],
+    pub full_answers: [bool; 2],
+}
+
+/// Whether an input file exists and is not empty, like the empty placeholder
+/// files created when scaffolding a day
+fn has_input(day: u8, input_mode: &InputMode, input_dir: &str) -> bool {
+    fs::metadata(input_path(day, input_mode, input_dir)).is_ok_and(|meta| meta.len() > 0)
+}
+
+/// Collects the status of all 25 days
+pub fn get_status(input_dir: &str) -> Result<Vec<DayStatus>, AocError> {
+    let quiet_ctx = |input_mode| Context::new(input_mode, Verbosity::Quiet);
+    let test_answers = Answers::load(&quiet_ctx(InputMode::Test), input_dir)?;
+    let full_answers = Answers::load(&quiet_ctx(InputMode::Full), input_dir)?;
+    let solutions = get_solutions();
+
+    let mut status = Vec::new();
+    for day in 1..=25 {
+        let registration = solutions.iter().find(|solution| solution.day == day);
+        let parts = match registration {
+            Some(registration) => registration
+                .implemented
+                .map(|implemented| match implemented {
+                    true => PartStatus::Done,
+                    false => PartStatus::Stub,
+                }),
+            None => [PartStatus::Missing; 2],
+        };
+
+        status.push(DayStatus {
+            day,
+            title: registration.map(|registration| registration.title),
+            parts,
+            test_input: has_input(day, &InputMode::Test, input_dir),
+            full_input: has_input(day, &InputMode::Full, input_dir),
+            test_answers: [1, 2].map(|part| test_answers.get(day, part).is_some()),
+            full_answers: [1, 2].map(|part| full_answers.get(day, part).is_some()),
+        });
+    }
+    return Ok(status);
+}
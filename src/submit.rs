@@ -0,0 +1,329 @@
+//! Submission of answers along with a local history of attempts
+//!
+//! Answers are posted to the same base URL and with the same session token as
+//! used for fetching inputs. Every attempt is recorded in a history file, e.g.:
+//!
+//! ```toml
+//! [[attempt]]
+//! day = 5
+//! part = 1
+//! answer = "6572"
+//! verdict = "correct"
+//! timestamp = 1638700000
+//! ```
+//!
+//! Before submitting, the history is consulted to refuse answers known to be
+//! wrong and to warn about answers contradicting a too-high/too-low bound.
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::fetch::Offline;
+use crate::{Answer, AocError};
+
+/// The verdict on a submitted answer
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Correct,
+    Wrong,
+    TooHigh,
+    TooLow,
+    RateLimited,
+    AlreadySolved,
+    Unknown,
+}
+
+impl Verdict {
+    /// Parses the verdict from the response to a submission
+    pub fn parse(response: &str) -> Verdict {
+        let response = response.to_lowercase();
+        if response.contains("that's the right answer") {
+            return Verdict::Correct;
+        } else if response.contains("answer too recently") {
+            return Verdict::RateLimited;
+        } else if response.contains("solving the right level") {
+            return Verdict::AlreadySolved;
+        } else if response.contains("your answer is too high") {
+            return Verdict::TooHigh;
+        } else if response.contains("your answer is too low") {
+            return Verdict::TooLow;
+        } else if response.contains("not the right answer") {
+            return Verdict::Wrong;
+        }
+        return Verdict::Unknown;
+    }
+
+    /// Whether the answer was rejected as wrong
+    pub fn is_wrong(&self) -> bool {
+        matches!(self, Verdict::Wrong | Verdict::TooHigh | Verdict::TooLow)
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "CORRECT"),
+            Verdict::Wrong => write!(f, "WRONG"),
+            Verdict::TooHigh => write!(f, "WRONG (too high)"),
+            Verdict::TooLow => write!(f, "WRONG (too low)"),
+            Verdict::RateLimited => write!(f, "RATE LIMITED"),
+            Verdict::AlreadySolved => write!(f, "ALREADY SOLVED"),
+            Verdict::Unknown => write!(f, "UNKNOWN RESPONSE"),
+        }
+    }
+}
+
+/// Extracts the waiting time from a rate-limited response, e.g. `"4m 2s"`
+pub fn parse_wait_time(response: &str) -> Option<String> {
+    let start = response.find("You have ")? + "You have ".len();
+    let end = start + response[start..].find(" left to wait")?;
+    return Some(response[start..end].to_string());
+}
+
+// -----------------------------------------------------------------------------
+
+/// A submission attempt as recorded in the history
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Attempt {
+    pub day: u8,
+    pub part: u8,
+    pub answer: String,
+    pub verdict: Verdict,
+    pub timestamp: u64,
+}
+
+/// The history of all submission attempts
+#[derive(Default, Serialize, Deserialize)]
+pub struct History {
+    #[serde(default)]
+    attempt: Vec<Attempt>,
+}
+
+impl History {
+    /// Loads the history file; if there is none, the history is empty
+    pub fn load(filepath: &Path) -> Result<History, AocError> {
+        if !filepath.exists() {
+            return Ok(History::default());
+        }
+        let content = fs::read_to_string(filepath).map_err(|source| AocError::Io {
+            path: filepath.to_path_buf(),
+            source,
+        })?;
+        return toml::from_str(&content).map_err(|err| {
+            AocError::InvalidInput(format!("Invalid history file {:?}:\n{}", filepath, err))
+        });
+    }
+
+    /// Writes the history file, creating its directory if needed
+    pub fn save(&self, filepath: &Path) -> Result<(), AocError> {
+        let content = toml::to_string(self).expect("Failed serializing history!");
+        let io_error = |source| AocError::Io {
+            path: filepath.to_path_buf(),
+            source,
+        };
+        if let Some(dir) = filepath.parent() {
+            fs::create_dir_all(dir).map_err(io_error)?;
+        }
+        return fs::write(filepath, content).map_err(io_error);
+    }
+
+    /// Records an attempt, timestamped with the current time
+    pub fn record(&mut self, day: u8, part: u8, answer: &Answer, verdict: Verdict) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        self.attempt.push(Attempt {
+            day,
+            part,
+            answer: answer.to_string(),
+            verdict,
+            timestamp,
+        });
+    }
+
+    /// The recorded attempts for a day and part, in chronological order
+    pub fn attempts(&self, day: u8, part: u8) -> impl Iterator<Item = &Attempt> {
+        self.attempt
+            .iter()
+            .filter(move |attempt| attempt.day == day && attempt.part == part)
+    }
+
+    /// Checks an answer against the history before submitting it
+    ///
+    /// Returns an error if the part is solved already or if the answer is
+    /// known to be wrong, and otherwise the warnings about recorded bounds
+    /// the answer contradicts.
+    pub fn vet(&self, day: u8, part: u8, answer: &Answer) -> Result<Vec<String>, AocError> {
+        let answer_str = answer.to_string();
+        let answer_num = answer_str.parse::<i128>().ok();
+        let mut warnings = Vec::new();
+
+        for attempt in self.attempts(day, part) {
+            if attempt.verdict == Verdict::Correct {
+                return Err(AocError::Submission(format!(
+                    "Day {:02} part {} is solved already with answer {}!",
+                    day, part, attempt.answer
+                )));
+            }
+            if attempt.verdict.is_wrong() && attempt.answer == answer_str {
+                return Err(AocError::Submission(format!(
+                    "Answer {} was submitted before and is {}!",
+                    answer_str, attempt.verdict
+                )));
+            }
+
+            let (num, bound) = match (answer_num, attempt.answer.parse::<i128>()) {
+                (Some(num), Ok(bound)) => (num, bound),
+                _ => continue,
+            };
+            match attempt.verdict {
+                Verdict::TooHigh if num >= bound => warnings.push(format!(
+                    "Answer {} is not below {}, which is known to be too high",
+                    answer_str, bound
+                )),
+                Verdict::TooLow if num <= bound => warnings.push(format!(
+                    "Answer {} is not above {}, which is known to be too low",
+                    answer_str, bound
+                )),
+                _ => {}
+            }
+        }
+
+        return Ok(warnings);
+    }
+}
+
+// -----------------------------------------------------------------------------
+
+/// A backend for submitting answers
+pub trait Submit {
+    /// Submits an answer for a day and part, returning the raw response
+    fn submit(&self, day: u8, part: u8, answer: &Answer) -> Result<String, AocError>;
+}
+
+/// Submits answers via HTTP, authenticated by a session token
+pub struct HttpSubmitter {
+    pub base_url: String,
+    session: Option<String>,
+}
+
+impl HttpSubmitter {
+    pub fn new(base_url: &str, session: Option<String>) -> HttpSubmitter {
+        HttpSubmitter {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session,
+        }
+    }
+}
+
+impl Submit for HttpSubmitter {
+    fn submit(&self, day: u8, part: u8, answer: &Answer) -> Result<String, AocError> {
+        let session = match &self.session {
+            Some(session) => session,
+            None => {
+                return Err(AocError::Submission(String::from(
+                    "no session token, set AOC_SESSION",
+                )))
+            }
+        };
+
+        let url = format!("{}/2021/day/{}/answer", self.base_url, day);
+        let response = ureq::post(&url)
+            .set("Cookie", &format!("session={}", session))
+            .set("User-Agent", "aoc21 answer submitter")
+            .send_form(&[
+                ("level", &part.to_string()),
+                ("answer", &answer.to_string()),
+            ])
+            .map_err(|err| AocError::Submission(err.to_string()))?;
+        return response
+            .into_string()
+            .map_err(|err| AocError::Submission(err.to_string()));
+    }
+}
+
+/// Refuses to submit anything, for working offline
+impl Submit for Offline {
+    fn submit(&self, _day: u8, _part: u8, _answer: &Answer) -> Result<String, AocError> {
+        Err(AocError::Submission(String::from(
+            "cannot submit while running in offline mode",
+        )))
+    }
+}
+
+// -----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RIGHT: &str = "<p>That's the right answer!  You are one gold star closer.</p>";
+    const TOO_HIGH: &str = "<p>That's not the right answer; your answer is too high.</p>";
+    const TOO_LOW: &str = "<p>That's not the right answer; your answer is too low.</p>";
+    const WRONG: &str = "<p>That's not the right answer.  If you're stuck, ...</p>";
+    const RATE_LIMITED: &str = "<p>You gave an answer too recently; you have to wait after \
+                                submitting an answer before trying again.  You have 4m 2s left \
+                                to wait.</p>";
+    const ALREADY_SOLVED: &str = "<p>You don't seem to be solving the right level.  Did you \
+                                  already complete it?</p>";
+
+    fn history(attempts: &[(i64, Verdict)]) -> History {
+        let mut history = History::default();
+        for &(answer, verdict) in attempts {
+            history.record(5, 2, &Answer::Int(answer), verdict);
+        }
+        return history;
+    }
+
+    #[test]
+    fn parses_verdicts() {
+        assert_eq!(Verdict::parse(RIGHT), Verdict::Correct);
+        assert_eq!(Verdict::parse(TOO_HIGH), Verdict::TooHigh);
+        assert_eq!(Verdict::parse(TOO_LOW), Verdict::TooLow);
+        assert_eq!(Verdict::parse(WRONG), Verdict::Wrong);
+        assert_eq!(Verdict::parse(RATE_LIMITED), Verdict::RateLimited);
+        assert_eq!(Verdict::parse(ALREADY_SOLVED), Verdict::AlreadySolved);
+        assert_eq!(Verdict::parse("<p>Oops</p>"), Verdict::Unknown);
+    }
+
+    #[test]
+    fn parses_wait_time() {
+        assert_eq!(parse_wait_time(RATE_LIMITED), Some(String::from("4m 2s")));
+        assert_eq!(parse_wait_time(TOO_HIGH), None);
+    }
+
+    #[test]
+    fn refuses_known_wrong_answers() {
+        let history = history(&[(100, Verdict::Wrong), (200, Verdict::TooHigh)]);
+        for answer in [100, 200] {
+            assert!(matches!(
+                history.vet(5, 2, &Answer::Int(answer)),
+                Err(AocError::Submission(_))
+            ));
+        }
+        assert!(history.vet(5, 1, &Answer::Int(100)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn refuses_solved_parts() {
+        let history = history(&[(150, Verdict::Correct)]);
+        assert!(matches!(
+            history.vet(5, 2, &Answer::Int(151)),
+            Err(AocError::Submission(_))
+        ));
+    }
+
+    #[test]
+    fn warns_about_contradicted_bounds() {
+        let history = history(&[(200, Verdict::TooHigh), (100, Verdict::TooLow)]);
+        assert!(history.vet(5, 2, &Answer::Int(150)).unwrap().is_empty());
+        assert_eq!(history.vet(5, 2, &Answer::Int(250)).unwrap().len(), 1);
+        assert_eq!(history.vet(5, 2, &Answer::Int(50)).unwrap().len(), 1);
+        assert!(history.vet(5, 2, &Answer::from("abc")).unwrap().is_empty());
+    }
+}
@@ -1,7 +1,215 @@
 //! Utility function definitions and type aliases
 
+use std::fs;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde::Serialize;
+
+use crate::fetch::{is_missing, read_or_fetch, Fetch};
+use crate::AocError;
+
 /// A selector for the various input modes to a puzzle solution function
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum InputMode {
     Full,
     Test,
 }
+
+/// Where the input to a puzzle solution function is read from
+///
+/// This is independent of the input mode passed to the solution, which only
+/// selects the default input file within an input directory.
+#[derive(Clone, Debug)]
+pub enum InputSource {
+    /// The default input file for the day and input mode in a directory;
+    /// missing full inputs are fetched and cached there
+    Dir {
+        dir: PathBuf,
+        fetcher: Arc<dyn Fetch>,
+    },
+    /// An arbitrary input file
+    File(PathBuf),
+    /// The standard input, given as `-` on the command line
+    Stdin,
+}
+
+impl InputSource {
+    /// Constructs the source of the default input files in a directory
+    pub fn dir(input_dir: &str, fetcher: Arc<dyn Fetch>) -> InputSource {
+        InputSource::Dir {
+            dir: PathBuf::from(input_dir),
+            fetcher,
+        }
+    }
+
+    /// Parses a command line argument, i.e. a file path or `-` for stdin
+    pub fn from_arg(arg: &str) -> InputSource {
+        match arg {
+            "-" => InputSource::Stdin,
+            path => InputSource::File(PathBuf::from(path)),
+        }
+    }
+
+    /// The path the input for a day and input mode is read from; `-` for stdin
+    pub fn path(&self, day: u8, input_mode: &InputMode) -> PathBuf {
+        match self {
+            InputSource::Dir { dir, .. } => input_path(day, input_mode, &dir.to_string_lossy()),
+            InputSource::File(path) => path.clone(),
+            InputSource::Stdin => PathBuf::from("-"),
+        }
+    }
+
+    /// Opens the input for a day and input mode to read it line by line,
+    /// without any output; missing full inputs are fetched first
+    pub fn open(&self, day: u8, input_mode: &InputMode) -> Result<Box<dyn BufRead>, AocError> {
+        let path = self.path(day, input_mode);
+        match self {
+            InputSource::Stdin => return Ok(Box::new(io::stdin().lock())),
+            InputSource::Dir { fetcher, .. }
+                if *input_mode == InputMode::Full && is_missing(&path) =>
+            {
+                read_or_fetch(&path, day, fetcher.as_ref())?;
+            }
+            _ => {}
+        }
+        let file = File::open(&path).map_err(|source| AocError::Io { path, source })?;
+        return Ok(Box::new(BufReader::new(file)));
+    }
+
+    /// Reads the input for a day and input mode, without any output
+    pub fn read(&self, day: u8, input_mode: &InputMode) -> Result<String, AocError> {
+        match self {
+            InputSource::Dir { fetcher, .. } if *input_mode == InputMode::Full => {
+                read_or_fetch(&self.path(day, input_mode), day, fetcher.as_ref())
+            }
+            InputSource::Stdin => {
+                let mut input = String::new();
+                io::stdin()
+                    .read_to_string(&mut input)
+                    .map_err(|source| AocError::Io {
+                        path: PathBuf::from("<stdin>"),
+                        source,
+                    })?;
+                return Ok(input);
+            }
+            _ => read_input(&self.path(day, input_mode)),
+        }
+    }
+}
+
+/// How much output to produce, from only the results to debug traces
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    Quiet,
+    Normal,
+    Verbose,
+    Debug,
+}
+
+/// The context a solution function is invoked in
+#[derive(Clone, Copy)]
+pub struct Context {
+    pub input_mode: InputMode,
+    pub verbosity: Verbosity,
+    /// The size of the sliding window, overriding the default of a solution
+    /// which analyzes its input in windows (see day 01)
+    pub window: Option<usize>,
+}
+
+impl Context {
+    pub fn new(input_mode: InputMode, verbosity: Verbosity) -> Context {
+        Context {
+            input_mode,
+            verbosity,
+            window: None,
+        }
+    }
+
+    /// Sets the size of the sliding window
+    pub fn with_window(self, window: Option<usize>) -> Context {
+        Context { window, ..self }
+    }
+}
+
+/// Prints a message if the context's verbosity is at least the given level
+///
+/// Messages go to stderr, such that stdout only carries the results.
+#[macro_export]
+macro_rules! log {
+    ($ctx:expr, $level:expr, $($arg:tt)*) => {
+        if $ctx.verbosity >= $level {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+/// Prints a message unless running quietly
+#[macro_export]
+macro_rules! info {
+    ($ctx:expr, $($arg:tt)*) => {
+        $crate::log!($ctx, $crate::Verbosity::Normal, $($arg)*)
+    };
+}
+
+/// Prints a message in verbose mode (`-v`), e.g. intermediate results
+#[macro_export]
+macro_rules! verbose {
+    ($ctx:expr, $($arg:tt)*) => {
+        $crate::log!($ctx, $crate::Verbosity::Verbose, $($arg)*)
+    };
+}
+
+/// Prints a message in debug mode (`-vv`), e.g. traces of every step
+#[macro_export]
+macro_rules! debug {
+    ($ctx:expr, $($arg:tt)*) => {
+        $crate::log!($ctx, $crate::Verbosity::Debug, $($arg)*)
+    };
+}
+
+/// Constructs the path of the input file for a certain day and input mode
+pub fn input_path(day: u8, input_mode: &InputMode, input_dir: &str) -> PathBuf {
+    let dir = Path::new(input_dir);
+    match input_mode {
+        InputMode::Test => dir.join(format!("day{:02}_test.txt", day)),
+        InputMode::Full => dir.join(format!("day{:02}.txt", day)),
+    }
+}
+
+/// Reads an input file as an unprocessed string, without any output
+pub fn read_input(filepath: &Path) -> Result<String, AocError> {
+    fs::read_to_string(filepath).map_err(|source| AocError::Io {
+        path: filepath.to_path_buf(),
+        source,
+    })
+}
+
+/// Load the input from the given source as an unprocessed string
+pub fn load_input(
+    day: u8,
+    _part: u8,
+    ctx: &Context,
+    source: &InputSource,
+) -> Result<String, AocError> {
+    match source {
+        InputSource::Stdin => info!(ctx, "Loading input from stdin ..."),
+        _ => info!(
+            ctx,
+            "Loading input from:\n  {:?}",
+            source.path(day, &ctx.input_mode)
+        ),
+    }
+    let input = source.read(day, &ctx.input_mode)?;
+    info!(
+        ctx,
+        "Loaded input. (Length: {}, Lines: {})",
+        input.len(),
+        input.matches("\n").count()
+    );
+
+    return Ok(input);
+}
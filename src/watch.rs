@@ -0,0 +1,100 @@
+//! Polling for changed files, e.g. to rerun a solution on changed input
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// Watches files for changes by polling their modification times
+///
+/// Files are watched either individually or via a name prefix within a
+/// directory, in which case files created later are picked up as well.
+#[derive(Default)]
+pub struct FileWatcher {
+    files: Vec<PathBuf>,
+    prefixes: Vec<(PathBuf, String)>,
+    snapshot: HashMap<PathBuf, SystemTime>,
+}
+
+impl FileWatcher {
+    pub fn new() -> FileWatcher {
+        FileWatcher::default()
+    }
+
+    /// Watches a single file
+    pub fn watch_file(&mut self, path: &Path) {
+        self.files.push(path.to_path_buf());
+        self.snapshot = self.scan();
+    }
+
+    /// Watches all files in a directory whose names start with a prefix; the
+    /// files in matching subdirectories are watched as well
+    pub fn watch_prefix(&mut self, dir: &Path, prefix: &str) {
+        self.prefixes.push((dir.to_path_buf(), prefix.to_string()));
+        self.snapshot = self.scan();
+    }
+
+    /// The watched files which currently exist, along with their modification
+    /// times
+    fn scan(&self) -> HashMap<PathBuf, SystemTime> {
+        let mut paths = self.files.clone();
+        for (dir, prefix) in self.prefixes.iter() {
+            for path in list_dir(dir) {
+                let matches = path
+                    .file_name()
+                    .is_some_and(|name| name.to_string_lossy().starts_with(prefix.as_str()));
+                match (matches, path.is_dir()) {
+                    (true, true) => paths.extend(list_dir(&path)),
+                    (true, false) => paths.push(path),
+                    (false, _) => {}
+                }
+            }
+        }
+
+        return paths
+            .into_iter()
+            .filter_map(|path| {
+                let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+                Some((path, modified))
+            })
+            .collect();
+    }
+
+    /// Blocks until any watched file is modified, created, or removed, and
+    /// returns the changed files
+    pub fn wait(&mut self, interval: Duration) -> Vec<PathBuf> {
+        loop {
+            thread::sleep(interval);
+            let snapshot = self.scan();
+
+            let mut changed = snapshot
+                .iter()
+                .filter(|(path, modified)| self.snapshot.get(*path) != Some(modified))
+                .map(|(path, _)| path.clone())
+                .chain(
+                    self.snapshot
+                        .keys()
+                        .filter(|path| !snapshot.contains_key(*path))
+                        .cloned(),
+                )
+                .collect::<Vec<_>>();
+            self.snapshot = snapshot;
+
+            if !changed.is_empty() {
+                changed.sort();
+                return changed;
+            }
+        }
+    }
+}
+
+/// Lists the entries of a directory; unreadable directories count as empty
+fn list_dir(dir: &Path) -> Vec<PathBuf> {
+    match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| Some(entry.ok()?.path()))
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
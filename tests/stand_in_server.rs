@@ -0,0 +1,159 @@
+//! Fetching inputs and submitting answers against a local stand-in server,
+//! configured via `AOC_BASE_URL` like the real one
+
+use std::env;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+const DAY01_INPUT: &str = "199\n200\n208\n210\n200\n207\n240\n269\n260\n263\n";
+const DAY01_PART1: &str = "7";
+
+/// Reads a request, returning its request line and body
+fn read_request(stream: &TcpStream) -> (String, String) {
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).unwrap();
+
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header).unwrap();
+        if header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap();
+            }
+        }
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).unwrap();
+    return (request_line, String::from_utf8(body).unwrap());
+}
+
+/// Serves the input of day 01, an empty input for day 02, and verdicts on the
+/// answers for day 01 part 1; returns the base URL and the number of fetches
+fn serve() -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let num_fetches = Arc::new(AtomicUsize::new(0));
+
+    let counter = num_fetches.clone();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let (request_line, body) = read_request(&stream);
+            let response = match request_line.split_whitespace().nth(1).unwrap_or("") {
+                "/2021/day/1/input" => {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    DAY01_INPUT
+                }
+                "/2021/day/2/input" => "",
+                "/2021/day/1/answer" => match body.contains(&format!("answer={}", DAY01_PART1)) {
+                    true => "<p>That's the right answer!</p>",
+                    false => "<p>That's not the right answer; your answer is too high.</p>",
+                },
+                _ => "Not found",
+            };
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                response.len(),
+                response
+            )
+            .unwrap();
+        }
+    });
+    return (base_url, num_fetches);
+}
+
+/// Creates an empty working directory for the CLI
+fn work_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("aoc21-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    return dir;
+}
+
+/// Runs the CLI in the working directory against the stand-in server
+fn aoc21(dir: &Path, base_url: &str, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_aoc21"))
+        .args(args)
+        .current_dir(dir)
+        .env("AOC_BASE_URL", base_url)
+        .env("AOC_SESSION", "53616c7465645f5f")
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn fetches_and_caches_input() {
+    let (base_url, num_fetches) = serve();
+    let dir = work_dir("fetch");
+
+    for _ in 0..2 {
+        let output = aoc21(&dir, &base_url, &["run", "1", "1", "-q"]);
+        assert!(output.status.success());
+        assert!(String::from_utf8_lossy(&output.stdout).contains(DAY01_PART1));
+    }
+    assert_eq!(num_fetches.load(Ordering::SeqCst), 1);
+    assert_eq!(
+        fs::read_to_string(dir.join("input/day01.txt")).unwrap(),
+        DAY01_INPUT
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn fetches_once_for_parallel_parts() {
+    let (base_url, num_fetches) = serve();
+    let dir = work_dir("parallel");
+
+    let output = aoc21(&dir, &base_url, &["run", "1", "--jobs", "2", "-q"]);
+    assert!(output.status.success());
+    assert_eq!(num_fetches.load(Ordering::SeqCst), 1);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn rejects_empty_input() {
+    let (base_url, _) = serve();
+    let dir = work_dir("empty");
+
+    let output = aoc21(&dir, &base_url, &["run", "2", "1", "-q"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(!dir.join("input/day02.txt").exists());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn submits_answers() {
+    let (base_url, _) = serve();
+    let dir = work_dir("submit");
+
+    let output = aoc21(&dir, &base_url, &["submit", "1", "1", "8", "-q"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(1), "{}{}", stdout, stderr);
+    assert!(stdout.contains("too high"));
+
+    let output = aoc21(&dir, &base_url, &["submit", "1", "1", "-q"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("CORRECT"));
+
+    let history = fs::read_to_string(dir.join("input/submissions.toml")).unwrap();
+    assert!(history.contains("too_high") && history.contains("correct"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}